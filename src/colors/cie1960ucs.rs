@@ -0,0 +1,56 @@
+use nalgebra::Vector3;
+
+use super::{Color, CIE1976UCS, CIEXYZ};
+
+/// A color represented by its CIE 1960 UCS chromaticity coordinates u and v, and its luminance Y.
+///
+/// This is the predecessor of the CIE 1976 UCS, with v = 2v' / 3 and u = u'. It has been
+/// superseded for most purposes, but correlated color temperature and Duv are still defined in
+/// it.
+///
+/// See https://en.wikipedia.org/wiki/CIE_1960_color_space for more information.
+#[derive(Clone, Copy, Debug)]
+pub struct CIE1960UCS {
+    vec: Vector3<f64>, // Components are u, v, and Y
+}
+
+impl Color for CIE1960UCS {
+    fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
+        let v = CIE1976UCS::from_ciexyz(ciexyz).to_vector3();
+        Self::from_vector3(Vector3::new(v[0], v[1] * 2.0 / 3.0, v[2]))
+    }
+
+    fn to_ciexyz(&self) -> CIEXYZ {
+        let v = self.to_vector3();
+        CIE1976UCS::from_vector3(Vector3::new(v[0], v[1] * 1.5, v[2])).to_ciexyz()
+    }
+
+    fn from_vector3(vec: Vector3<f64>) -> Self {
+        CIE1960UCS { vec }
+    }
+
+    fn to_vector3(&self) -> Vector3<f64> {
+        self.vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::get_d65_ciexyz;
+
+    #[test]
+    fn test_ucs_white() {
+        let white = CIE1960UCS::from_ciexyz(&get_d65_ciexyz());
+        let v = white.to_vector3();
+        assert!((v[0] - 0.1978).abs() < 1e-4);
+        assert!((v[1] - 0.3122).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ucs_round_trip() {
+        let ciexyz = CIEXYZ::new(0.2, 0.3, 0.4);
+        let round_trip = CIE1960UCS::from_ciexyz(&ciexyz).to_ciexyz();
+        assert!((ciexyz.to_vector3() - round_trip.to_vector3()).magnitude() < 1e-12);
+    }
+}
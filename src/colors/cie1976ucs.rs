@@ -0,0 +1,83 @@
+use nalgebra::Vector3;
+
+use super::{get_d65_ciexyz, Color, CIEXYZ};
+
+/// A color represented by its CIE 1976 UCS chromaticity coordinates u' and v', and its luminance
+/// Y.
+///
+/// The u'v' chromaticity diagram is a projective transformation of the CIE 1931 xy diagram that is
+/// more perceptually uniform, and is the basis of CIELUV.
+///
+/// See https://en.wikipedia.org/wiki/CIELUV for more information.
+///
+/// Black has no chromaticity, so it is given the chromaticity of the D65 white point.
+#[derive(Clone, Copy, Debug)]
+pub struct CIE1976UCS {
+    vec: Vector3<f64>, // Components are u', v', and Y
+}
+
+impl Color for CIE1976UCS {
+    #[allow(non_snake_case)]
+    fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
+        let ciexyz_vec = ciexyz.to_vector3();
+        let X = ciexyz_vec[0];
+        let Y = ciexyz_vec[1];
+        let Z = ciexyz_vec[2];
+        let denom = X + 15.0 * Y + 3.0 * Z;
+        if denom == 0.0 {
+            let white = Self::from_ciexyz(&get_d65_ciexyz()).to_vector3();
+            return Self::from_vector3(Vector3::new(white[0], white[1], Y));
+        }
+        Self::from_vector3(Vector3::new(4.0 * X / denom, 9.0 * Y / denom, Y))
+    }
+
+    #[allow(non_snake_case)]
+    fn to_ciexyz(&self) -> CIEXYZ {
+        let v = self.to_vector3();
+        let u_prime = v[0];
+        let v_prime = v[1];
+        let Y = v[2];
+        if v_prime == 0.0 {
+            return CIEXYZ::new(0.0, 0.0, 0.0);
+        }
+        let X = Y * 9.0 * u_prime / (4.0 * v_prime);
+        let Z = Y * (12.0 - 3.0 * u_prime - 20.0 * v_prime) / (4.0 * v_prime);
+        CIEXYZ::from_vector3(Vector3::new(X, Y, Z))
+    }
+
+    fn from_vector3(vec: Vector3<f64>) -> Self {
+        CIE1976UCS { vec }
+    }
+
+    fn to_vector3(&self) -> Vector3<f64> {
+        self.vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ucs_white() {
+        let white = CIE1976UCS::from_ciexyz(&get_d65_ciexyz());
+        let v = white.to_vector3();
+        assert!((v[0] - 0.1978).abs() < 1e-4);
+        assert!((v[1] - 0.4683).abs() < 1e-4);
+        assert_eq!(v[2], 1.0);
+    }
+
+    #[test]
+    fn test_ucs_round_trip() {
+        let ciexyz = CIEXYZ::new(0.2, 0.3, 0.4);
+        let round_trip = CIE1976UCS::from_ciexyz(&ciexyz).to_ciexyz();
+        assert!((ciexyz.to_vector3() - round_trip.to_vector3()).magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn test_ucs_black() {
+        let black = CIE1976UCS::from_ciexyz(&CIEXYZ::new(0.0, 0.0, 0.0));
+        assert_eq!(black.to_vector3()[2], 0.0);
+        assert_eq!(black.to_ciexyz().to_vector3(), Vector3::zeros());
+    }
+}
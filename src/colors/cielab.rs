@@ -0,0 +1,117 @@
+use nalgebra::Vector3;
+
+use super::{get_d65_ciexyz, Color, CIEXYZ};
+
+/// A color represented in the CIE 1976 L*, a*, b* color space.
+///
+/// Like CIELUV, this color space is derived from the CIE 1931 XYZ color space and is intended to
+/// be approximately perceptually uniform. It is the basis for most color difference formulas.
+///
+/// See https://en.wikipedia.org/wiki/CIELAB_color_space for more information.
+///
+/// Unlike the Wikipedia article, and consistent with CIELUV, we normalize L to [0, 1] and scale a
+/// and b by the same factor.
+#[derive(Clone, Copy, Debug)]
+pub struct CIELAB {
+    vec: Vector3<f64>, // Components are L, a, and b
+}
+
+impl CIELAB {
+    pub fn chroma(&self) -> f64 {
+        let a = self.vec[1];
+        let b = self.vec[2];
+        (a.powf(2.0) + b.powf(2.0)).sqrt()
+    }
+
+    // In radians
+    pub fn hue(&self) -> f64 {
+        let a = self.vec[1];
+        let b = self.vec[2];
+        b.atan2(a)
+    }
+
+    pub fn as_lch(&self) -> (f64, f64, f64) {
+        (self.vec[0], self.chroma(), self.hue())
+    }
+
+    pub fn from_lch(l: f64, c: f64, h: f64) -> Self {
+        Self::from_vector3(Vector3::new(l, c * h.cos(), c * h.sin()))
+    }
+}
+
+const EPSILON: f64 = (6.0 / 29.0) * (6.0 / 29.0) * (6.0 / 29.0);
+
+fn f(t: f64) -> f64 {
+    if t > EPSILON {
+        t.cbrt()
+    } else {
+        t / (3.0 * (6.0_f64 / 29.0).powf(2.0)) + 4.0 / 29.0
+    }
+}
+
+fn f_inverse(t: f64) -> f64 {
+    if t > 6.0 / 29.0 {
+        t.powf(3.0)
+    } else {
+        3.0 * (6.0_f64 / 29.0).powf(2.0) * (t - 4.0 / 29.0)
+    }
+}
+
+impl Color for CIELAB {
+    fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
+        let v = ciexyz.to_vector3();
+        let white = get_d65_ciexyz().to_vector3();
+
+        let fx = f(v[0] / white[0]);
+        let fy = f(v[1] / white[1]);
+        let fz = f(v[2] / white[2]);
+
+        let l = (116.0 * fy - 16.0) / 100.0;
+        let a = 5.0 * (fx - fy);
+        let b = 2.0 * (fy - fz);
+
+        Self::from_vector3(Vector3::new(l, a, b))
+    }
+
+    fn to_ciexyz(&self) -> CIEXYZ {
+        let v = self.to_vector3();
+        let white = get_d65_ciexyz().to_vector3();
+
+        let fy = (v[0] * 100.0 + 16.0) / 116.0;
+        let fx = fy + v[1] / 5.0;
+        let fz = fy - v[2] / 2.0;
+
+        CIEXYZ::from_vector3(Vector3::new(
+            white[0] * f_inverse(fx),
+            white[1] * f_inverse(fy),
+            white[2] * f_inverse(fz),
+        ))
+    }
+
+    fn from_vector3(vec: Vector3<f64>) -> Self {
+        CIELAB { vec }
+    }
+
+    fn to_vector3(&self) -> Vector3<f64> {
+        self.vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lab_white() {
+        let white = CIELAB::from_ciexyz(&get_d65_ciexyz());
+        let v = white.to_vector3();
+        assert!((v - Vector3::new(1.0, 0.0, 0.0)).magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn test_lab_round_trip() {
+        let lab = CIELAB::from_vector3(Vector3::new(0.05, 0.2, -0.3));
+        let round_trip = CIELAB::from_ciexyz(&lab.to_ciexyz());
+        assert!((lab.to_vector3() - round_trip.to_vector3()).magnitude() < 1e-12);
+    }
+}
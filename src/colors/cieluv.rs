@@ -1,6 +1,6 @@
 use nalgebra::Vector3;
 
-use super::{get_d65_ciexyz, Color, CIEXYZ};
+use super::{get_d65_ciexyz, Color, CIE1976UCS, CIEXYZ};
 
 /// A color represented in the CIE 1976 L*, u*, v* color space.
 ///
@@ -38,16 +38,9 @@ impl CIELUV {
     }
 }
 
-#[allow(non_snake_case)]
 fn uv_chromaticities(color: &CIEXYZ) -> (f64, f64) {
-    let color_vec = color.to_vector3();
-    let X = color_vec[0];
-    let Y = color_vec[1];
-    let Z = color_vec[2];
-    (
-        (4.0 * X) / (X + 15.0 * Y + 3.0 * Z),
-        (9.0 * Y) / (X + 15.0 * Y + 3.0 * Z),
-    )
+    let ucs = CIE1976UCS::from_ciexyz(color).to_vector3();
+    (ucs[0], ucs[1])
 }
 
 impl Color for CIELUV {
@@ -61,13 +54,18 @@ impl Color for CIELUV {
 
         let normalized_Y = Y / white_Y;
 
-        let l = if normalized_Y <= (6.0 / 29.0 as f64).powf(3.0) {
-            ((29.0 / 3.0 as f64).powf(3.0) * normalized_Y) / 100.0
+        let l = if normalized_Y <= (6.0_f64 / 29.0).powf(3.0) {
+            ((29.0_f64 / 3.0).powf(3.0) * normalized_Y) / 100.0
         } else {
             (116.0 * normalized_Y.powf(1.0 / 3.0) - 16.0) / 100.0
         };
-        let (u_prime, v_prime) = uv_chromaticities(ciexyz);
         let (u_prime_white, v_prime_white) = uv_chromaticities(&white);
+        // Black has no chromaticity, so treat it as achromatic.
+        let (u_prime, v_prime) = if Y > 0.0 {
+            uv_chromaticities(ciexyz)
+        } else {
+            (u_prime_white, v_prime_white)
+        };
 
         let u = 13.0 * l * (u_prime - u_prime_white);
         let v = 13.0 * l * (v_prime - v_prime_white);
@@ -82,6 +80,10 @@ impl Color for CIELUV {
         let u = v[1];
         let v = v[2];
 
+        if l <= 0.0 {
+            return CIEXYZ::new(0.0, 0.0, 0.0);
+        }
+
         let white: CIEXYZ = get_d65_ciexyz();
         let white_Y = white.to_vector3()[1];
         let (u_prime_white, v_prime_white) = uv_chromaticities(&white);
@@ -90,7 +92,7 @@ impl Color for CIELUV {
         let v_prime = v / (13.0 * l) + v_prime_white;
 
         let Y = if (l * 100.0) <= 8.0 {
-            white_Y * (l * 100.0) * (3.0 / 29.0 as f64).powf(3.0)
+            white_Y * (l * 100.0) * (3.0_f64 / 29.0).powf(3.0)
         } else {
             white_Y * (((l * 100.0) + 16.0) / 116.0).powf(3.0)
         };
@@ -135,4 +137,11 @@ mod tests {
 
         assert_eq!(color.as_lch(), (lightness, chroma, hue));
     }
+
+    #[test]
+    fn test_luv_black() {
+        let black = CIELUV::from_ciexyz(&CIEXYZ::new(0.0, 0.0, 0.0));
+        assert_eq!(black.to_vector3(), Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(black.to_ciexyz().to_vector3(), Vector3::new(0.0, 0.0, 0.0));
+    }
 }
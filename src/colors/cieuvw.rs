@@ -0,0 +1,76 @@
+use nalgebra::Vector3;
+
+use super::{get_d65_ciexyz, Color, CIE1960UCS, CIEXYZ};
+
+/// A color represented in the CIE 1964 U*, V*, W* color space.
+///
+/// This color space is derived from the CIE 1960 UCS, and was an early attempt at a perceptually
+/// uniform color space before it was superseded by CIELUV. It is still used by the CIE color
+/// rendering index.
+///
+/// See https://en.wikipedia.org/wiki/CIE_1964_color_space for more information.
+///
+/// As with CIELUV, we divide all components by 100, so W is roughly in [0, 1]. Note that W is
+/// negative for very dark colors with Y < 0.31%.
+#[derive(Clone, Copy, Debug)]
+pub struct CIEUVW {
+    vec: Vector3<f64>, // Components are U, V, and W
+}
+
+impl Color for CIEUVW {
+    #[allow(non_snake_case)]
+    fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
+        let white = CIE1960UCS::from_ciexyz(&get_d65_ciexyz()).to_vector3();
+        let ucs = CIE1960UCS::from_ciexyz(ciexyz).to_vector3();
+        let Y = ucs[2] / white[2];
+
+        let w = (25.0 * (Y * 100.0).cbrt() - 17.0) / 100.0;
+        let u = 13.0 * w * (ucs[0] - white[0]);
+        let v = 13.0 * w * (ucs[1] - white[1]);
+        Self::from_vector3(Vector3::new(u, v, w))
+    }
+
+    fn to_ciexyz(&self) -> CIEXYZ {
+        let white = CIE1960UCS::from_ciexyz(&get_d65_ciexyz()).to_vector3();
+        let vec = self.to_vector3();
+        let (u, v, w) = (vec[0], vec[1], vec[2]);
+
+        let luminance = white[2] * ((w * 100.0 + 17.0) / 25.0).powi(3) / 100.0;
+        // The chromaticity is lost when W is zero, so treat the color as achromatic.
+        let (u_ucs, v_ucs) = if w == 0.0 {
+            (white[0], white[1])
+        } else {
+            (u / (13.0 * w) + white[0], v / (13.0 * w) + white[1])
+        };
+        CIE1960UCS::from_vector3(Vector3::new(u_ucs, v_ucs, luminance)).to_ciexyz()
+    }
+
+    fn from_vector3(vec: Vector3<f64>) -> Self {
+        CIEUVW { vec }
+    }
+
+    fn to_vector3(&self) -> Vector3<f64> {
+        self.vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uvw_white() {
+        let white = CIEUVW::from_ciexyz(&get_d65_ciexyz());
+        let v = white.to_vector3();
+        assert!(v[0].abs() < 1e-12);
+        assert!(v[1].abs() < 1e-12);
+        assert!((v[2] - (25.0 * 100.0_f64.cbrt() - 17.0) / 100.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_uvw_round_trip() {
+        let ciexyz = CIEXYZ::new(0.2, 0.3, 0.4);
+        let round_trip = CIEUVW::from_ciexyz(&ciexyz).to_ciexyz();
+        assert!((ciexyz.to_vector3() - round_trip.to_vector3()).magnitude() < 1e-12);
+    }
+}
@@ -0,0 +1,237 @@
+use std::marker::PhantomData;
+
+use nalgebra::{Matrix3, Vector3};
+
+use super::{Color, MatrixTRCTransform};
+use crate::encodings::{PixelComponent, PixelLayout};
+use crate::errors::Error;
+use crate::errors::Error::PixelBufferError;
+
+/// A color space defined by RGB primaries and tone response curves, such as sRGB.
+pub trait RGBColorSpace: Color {
+    /// The transform between the color space and CIE XYZ. It is built once and shared.
+    fn matrix_trc_transform() -> &'static MatrixTRCTransform;
+}
+
+/// A precomputed conversion from one RGB color space to another.
+///
+/// Converting through `Color::to_color` maps each color to CIE XYZ and back. This instead fuses
+/// the source to CIE XYZ and CIE XYZ to destination matrices into a single 3x3 matrix, so each
+/// conversion is one matrix multiplication between the tone response curves.
+#[derive(Clone, Debug)]
+pub struct Converter<Source, Destination> {
+    source: &'static MatrixTRCTransform,
+    destination: &'static MatrixTRCTransform,
+    matrix: Matrix3<f64>,
+    color_spaces: PhantomData<(Source, Destination)>,
+}
+
+impl<Source, Destination> Converter<Source, Destination>
+where
+    Source: RGBColorSpace,
+    Destination: RGBColorSpace,
+{
+    pub fn new() -> Self {
+        let source = Source::matrix_trc_transform();
+        let destination = Destination::matrix_trc_transform();
+        Converter {
+            source,
+            destination,
+            matrix: destination.from_ciexyz_matrix() * source.to_ciexyz_matrix(),
+            color_spaces: PhantomData,
+        }
+    }
+
+    /// The matrix mapping linear source RGB to linear destination RGB.
+    pub fn matrix(&self) -> &Matrix3<f64> {
+        &self.matrix
+    }
+
+    /// Convert a color from the source color space to the destination color space.
+    pub fn convert(&self, color: &Source) -> Destination {
+        Destination::from_vector3(self.convert_vector3(color.to_vector3()))
+    }
+
+    /// Convert each color of a slice into the corresponding element of `output`.
+    pub fn convert_slice(
+        &self,
+        colors: &[Source],
+        output: &mut [Destination],
+    ) -> Result<(), Error> {
+        if colors.len() != output.len() {
+            return Err(PixelBufferError(format!(
+                "Cannot convert {} colors into {} colors",
+                colors.len(),
+                output.len()
+            )));
+        }
+        for (color, converted) in colors.iter().zip(output.iter_mut()) {
+            *converted = self.convert(color);
+        }
+        Ok(())
+    }
+
+    /// Convert every color of a slice.
+    pub fn convert_all(&self, colors: &[Source]) -> Vec<Destination> {
+        colors.iter().map(|color| self.convert(color)).collect()
+    }
+
+    /// Convert an interleaved pixel buffer, such as 8-bit RGBA image data, into `output`, which
+    /// has the same layout. Alpha is copied unchanged.
+    pub fn convert_pixels<T>(
+        &self,
+        pixels: &[T],
+        output: &mut [T],
+        layout: PixelLayout,
+    ) -> Result<(), Error>
+    where
+        T: PixelComponent,
+    {
+        check_pixel_buffer(pixels.len(), layout)?;
+        if pixels.len() != output.len() {
+            return Err(PixelBufferError(format!(
+                "Cannot convert {} pixel components into {} components",
+                pixels.len(),
+                output.len()
+            )));
+        }
+        let channels = layout.channels();
+        for (pixel, converted) in pixels
+            .chunks_exact(channels)
+            .zip(output.chunks_exact_mut(channels))
+        {
+            converted.copy_from_slice(pixel);
+            self.convert_pixel(converted);
+        }
+        Ok(())
+    }
+
+    /// Convert an interleaved pixel buffer in place. Alpha is left unchanged.
+    pub fn convert_pixels_in_place<T>(
+        &self,
+        pixels: &mut [T],
+        layout: PixelLayout,
+    ) -> Result<(), Error>
+    where
+        T: PixelComponent,
+    {
+        check_pixel_buffer(pixels.len(), layout)?;
+        for pixel in pixels.chunks_exact_mut(layout.channels()) {
+            self.convert_pixel(pixel);
+        }
+        Ok(())
+    }
+
+    fn convert_vector3(&self, v: Vector3<f64>) -> Vector3<f64> {
+        let linear = self.matrix * self.source.apply_trc(v);
+        self.destination.apply_trc_inverse(linear)
+    }
+
+    /// Convert the RGB components at the start of a pixel.
+    fn convert_pixel<T>(&self, pixel: &mut [T])
+    where
+        T: PixelComponent,
+    {
+        let v = Vector3::new(pixel[0].to_real(), pixel[1].to_real(), pixel[2].to_real());
+        let converted = self.convert_vector3(v);
+        for (component, value) in pixel.iter_mut().zip(converted.iter()) {
+            *component = T::from_real(*value);
+        }
+    }
+}
+
+fn check_pixel_buffer(length: usize, layout: PixelLayout) -> Result<(), Error> {
+    if length % layout.channels() != 0 {
+        return Err(PixelBufferError(format!(
+            "A buffer of {} components does not hold a whole number of {:?} pixels",
+            length, layout
+        )));
+    }
+    Ok(())
+}
+
+impl<Source, Destination> Default for Converter<Source, Destination>
+where
+    Source: RGBColorSpace,
+    Destination: RGBColorSpace,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{DisplayP3, SRGB};
+
+    #[test]
+    fn test_matches_conversion_through_ciexyz() -> Result<(), Error> {
+        let converter = Converter::<SRGB, DisplayP3>::new();
+        let inverse = Converter::<DisplayP3, SRGB>::new();
+        for hex in &["ff0000", "00ff00", "0000ff", "808080", "ffffff", "123456"] {
+            let srgb = SRGB::from_hex(hex)?;
+            let expected: DisplayP3 = srgb.to_color();
+            let actual = converter.convert(&srgb);
+            assert!((actual.to_vector3() - expected.to_vector3()).amax() < 1e-12);
+            let round_trip = inverse.convert(&actual);
+            assert!((round_trip.to_vector3() - srgb.to_vector3()).amax() < 1e-9);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_slice() -> Result<(), Error> {
+        let converter = Converter::<SRGB, DisplayP3>::new();
+        let colors = [SRGB::from_hex("ff0000")?, SRGB::from_hex("123456")?];
+        let converted = converter.convert_all(&colors);
+        let mut output = [DisplayP3::from_vector3(Vector3::zeros()); 2];
+        converter.convert_slice(&colors, &mut output)?;
+        for ((color, a), b) in colors.iter().zip(converted.iter()).zip(output.iter()) {
+            let expected: DisplayP3 = color.to_color();
+            assert!((a.to_vector3() - expected.to_vector3()).amax() < 1e-12);
+            assert_eq!(a.to_vector3(), b.to_vector3());
+        }
+        assert!(converter.convert_slice(&colors, &mut output[..1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_pixels() -> Result<(), Error> {
+        let converter = Converter::<SRGB, DisplayP3>::new();
+        let expected = DisplayP3::from_color(&SRGB::from_hex("ff0000")?).to_vector3();
+
+        let mut rgba = [255_u8, 0, 0, 17, 255, 0, 0, 200];
+        converter.convert_pixels_in_place(&mut rgba, PixelLayout::Rgba)?;
+        assert_eq!(rgba[3], 17);
+        assert_eq!(rgba[7], 200);
+        assert_eq!(&rgba[0..3], &rgba[4..7]);
+        assert_eq!(DisplayP3::from_vector3(expected).to_hex()?, "ea3323");
+        assert_eq!(&rgba[0..3], &[0xea, 0x33, 0x23]);
+
+        let pixels = [65535_u16, 0, 0];
+        let mut output = [0_u16; 3];
+        converter.convert_pixels(&pixels, &mut output, PixelLayout::Rgb)?;
+        let converted = Vector3::from_iterator(output.iter().map(|v| v.to_real()));
+        assert!((converted - expected).amax() < 1e-5);
+
+        let mut floats = [1.0_f32, 0.0, 0.0];
+        converter.convert_pixels_in_place(&mut floats, PixelLayout::Rgb)?;
+        let converted = Vector3::from_iterator(floats.iter().map(|v| v.to_real()));
+        assert!((converted - expected).amax() < 1e-6);
+
+        assert!(converter
+            .convert_pixels_in_place(&mut [0_u8; 5], PixelLayout::Rgb)
+            .is_err());
+        assert!(converter
+            .convert_pixels(&[0_u8; 4], &mut [0; 8], PixelLayout::Rgba)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_identity() {
+        let converter = Converter::<SRGB, SRGB>::default();
+        assert!((converter.matrix() - Matrix3::identity()).amax() < 1e-12);
+    }
+}
@@ -1,7 +1,9 @@
+use std::sync::{Arc, OnceLock};
+
 use nalgebra::{Matrix3, Vector3};
 
 use super::utils::{MatrixTRCTransform, ParametricCurveType3};
-use super::{Color, CIEXYZ};
+use super::{Color, RGBColorSpace, CIEXYZ};
 
 /// A color represented in the Apple Display P3 color space.
 ///
@@ -14,6 +16,13 @@ pub struct DisplayP3 {
     vec: Vector3<f64>,
 }
 
+impl RGBColorSpace for DisplayP3 {
+    /// The transform between Display P3 and CIE XYZ, as defined by Apple's Display P3 profile.
+    fn matrix_trc_transform() -> &'static MatrixTRCTransform {
+        matrix_trc_transform()
+    }
+}
+
 impl Color for DisplayP3 {
     fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
         let in_vec = ciexyz.to_vector3();
@@ -63,12 +72,17 @@ fn chromatic_adaptation_matrix() -> Matrix3<f64> {
     )
 }
 
-fn matrix_trc_transform() -> MatrixTRCTransform {
-    MatrixTRCTransform {
-        matrix: transform_matrix(),
-        red_trc: TONE_RESPONSE_CURVE,
-        green_trc: TONE_RESPONSE_CURVE,
-        blue_trc: TONE_RESPONSE_CURVE,
-        chromatic_adaptation_matrix: chromatic_adaptation_matrix(),
-    }
+fn matrix_trc_transform() -> &'static MatrixTRCTransform {
+    static TRANSFORM: OnceLock<MatrixTRCTransform> = OnceLock::new();
+    TRANSFORM.get_or_init(|| {
+        let curve = Arc::new(TONE_RESPONSE_CURVE);
+        MatrixTRCTransform::new(
+            transform_matrix(),
+            curve.clone(),
+            curve.clone(),
+            curve,
+            chromatic_adaptation_matrix(),
+        )
+        .expect("The Display P3 matrices are invertible.")
+    })
 }
@@ -0,0 +1,447 @@
+//! Single precision conversions for realtime use, such as previewing images.
+use std::iter::StepBy;
+use std::marker::PhantomData;
+use std::slice::Iter;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+use super::{
+    Color, DisplayP3, MatrixTRCTransform, ParametricCurveType3, RGBColorSpace, ToneResponseCurve,
+    CIEXYZ, SRGB,
+};
+use crate::encodings::PixelLayout;
+use crate::errors::Error;
+use crate::errors::Error::PixelBufferError;
+
+/// The number of pixels converted together. Each step of a conversion runs over a whole block of
+/// separate red, green and blue arrays, so that the compiler can vectorize the matrix and the
+/// parametric tone response curves, which have no branches.
+const BLOCK: usize = 64;
+
+/// A single precision conversion from one RGB color space to another, for pixel buffers.
+///
+/// This is the fast counterpart of `Converter`, for `RGBf32` colors and for buffers of 8-bit,
+/// 16-bit or float components. The tone response curves are applied with
+/// `ToneResponseCurve::apply_approx` and `apply_inverse_approx`, and 8-bit components are decoded
+/// with a 256-entry lookup table per channel. Results are within one step of 16-bit components
+/// of the double precision conversion.
+///
+/// Curves of ICC parametric type 3, such as those of sRGB and Display P3, are evaluated in
+/// vectorized loops. Other curves are called once per component.
+#[derive(Clone, Debug)]
+pub struct FastConverter<Source, Destination> {
+    source_curves: [FastCurve; 3],
+    destination_curves: [FastCurve; 3],
+    matrix: [[f32; 3]; 3],
+    decoding_tables: Box<[[f32; 256]; 3]>,
+    color_spaces: PhantomData<(Source, Destination)>,
+}
+
+impl<Source, Destination> FastConverter<Source, Destination>
+where
+    Source: RGBColorSpace,
+    Destination: RGBColorSpace,
+{
+    pub fn new() -> Self {
+        let source = Source::matrix_trc_transform();
+        let destination = Destination::matrix_trc_transform();
+        let fused = destination.from_ciexyz_matrix() * source.to_ciexyz_matrix();
+        let mut matrix = [[0.0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = fused[(i, j)] as f32;
+            }
+        }
+        let curves = [source.red_trc(), source.green_trc(), source.blue_trc()];
+        let mut decoding_tables = Box::new([[0.0; 256]; 3]);
+        for (table, curve) in decoding_tables.iter_mut().zip(curves.iter()) {
+            for (i, value) in table.iter_mut().enumerate() {
+                *value = curve.apply(i as f64 / 255.0) as f32;
+            }
+        }
+        FastConverter {
+            source_curves: FastCurve::resolve(source),
+            destination_curves: FastCurve::resolve(destination),
+            matrix,
+            decoding_tables,
+            color_spaces: PhantomData,
+        }
+    }
+
+    /// Convert a color from the source color space to the destination color space.
+    pub fn convert(&self, color: &RGBf32<Source>) -> RGBf32<Destination> {
+        let mut linear = color.components;
+        for (value, curve) in linear.iter_mut().zip(self.source_curves.iter()) {
+            curve.apply(std::slice::from_mut(value));
+        }
+        let mut components = [0.0; 3];
+        for ((component, curve), row) in components
+            .iter_mut()
+            .zip(self.destination_curves.iter())
+            .zip(&self.matrix)
+        {
+            *component = row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2];
+            curve.apply_inverse(std::slice::from_mut(component));
+        }
+        RGBf32::new(components)
+    }
+
+    /// Convert an interleaved buffer of float components in place. Alpha is left unchanged.
+    pub fn convert_f32(&self, pixels: &mut [f32], layout: PixelLayout) -> Result<(), Error> {
+        self.convert_blocks(pixels, layout, |channel, block, linear| {
+            for (value, component) in linear.iter_mut().zip(block) {
+                *value = *component;
+            }
+            self.source_curves[channel].apply(linear);
+        })
+    }
+
+    /// Convert an interleaved buffer of 8-bit components in place. Alpha is left unchanged.
+    pub fn convert_u8(&self, pixels: &mut [u8], layout: PixelLayout) -> Result<(), Error> {
+        self.convert_blocks(pixels, layout, |channel, block, linear| {
+            let table = &self.decoding_tables[channel];
+            for (value, component) in linear.iter_mut().zip(block) {
+                *value = table[usize::from(*component)];
+            }
+        })
+    }
+
+    /// Convert an interleaved buffer of 16-bit components in place. Alpha is left unchanged.
+    pub fn convert_u16(&self, pixels: &mut [u16], layout: PixelLayout) -> Result<(), Error> {
+        self.convert_blocks(pixels, layout, |channel, block, linear| {
+            for (value, component) in linear.iter_mut().zip(block) {
+                *value = f32::from(*component) / 65535.0;
+            }
+            self.source_curves[channel].apply(linear);
+        })
+    }
+
+    /// Decode, convert and encode the pixels one block at a time.
+    ///
+    /// `decode` fills the linear values of a channel from the components of a block, which are
+    /// strided by the number of channels.
+    fn convert_blocks<T, F>(
+        &self,
+        pixels: &mut [T],
+        layout: PixelLayout,
+        decode: F,
+    ) -> Result<(), Error>
+    where
+        T: FastComponent,
+        F: Fn(usize, StepBy<Iter<T>>, &mut [f32]),
+    {
+        let channels = layout.channels();
+        if pixels.len() % channels != 0 {
+            return Err(PixelBufferError(format!(
+                "A buffer of {} components does not hold a whole number of {:?} pixels",
+                pixels.len(),
+                layout
+            )));
+        }
+        let mut linear = [[0.0; BLOCK]; 3];
+        let mut converted = [[0.0; BLOCK]; 3];
+        for block in pixels.chunks_mut(BLOCK * channels) {
+            let count = block.len() / channels;
+            for (channel, values) in linear.iter_mut().enumerate() {
+                decode(
+                    channel,
+                    block[channel..].iter().step_by(channels),
+                    &mut values[..count],
+                );
+            }
+            apply_matrix(&self.matrix, &linear, &mut converted);
+            for (channel, values) in converted.iter_mut().enumerate() {
+                self.destination_curves[channel].apply_inverse(&mut values[..count]);
+                let components = block[channel..].iter_mut().step_by(channels);
+                for (component, value) in components.zip(values.iter()) {
+                    *component = T::from_f32(*value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Source, Destination> Default for FastConverter<Source, Destination>
+where
+    Source: RGBColorSpace,
+    Destination: RGBColorSpace,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Multiply planar RGB by a matrix. The loop has no branches or bounds checks, so that it
+/// compiles to SIMD instructions.
+fn apply_matrix(matrix: &[[f32; 3]; 3], input: &[[f32; BLOCK]; 3], output: &mut [[f32; BLOCK]; 3]) {
+    for (row, values) in matrix.iter().zip(output.iter_mut()) {
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = row[0] * input[0][i] + row[1] * input[1][i] + row[2] * input[2][i];
+        }
+    }
+}
+
+/// A tone response curve, resolved once per converter so that the loops over blocks of parametric
+/// curves call a concrete function without branches, rather than a trait object.
+#[derive(Clone, Debug)]
+enum FastCurve {
+    Parametric(ParametricCurveType3),
+    Other(Arc<dyn ToneResponseCurve>),
+}
+
+impl FastCurve {
+    fn resolve(transform: &MatrixTRCTransform) -> [FastCurve; 3] {
+        [
+            transform.red_trc(),
+            transform.green_trc(),
+            transform.blue_trc(),
+        ]
+        .map(|curve| match curve.parametric() {
+            Some((3, parameters)) => FastCurve::Parametric(ParametricCurveType3 {
+                g: parameters[0],
+                a: parameters[1],
+                b: parameters[2],
+                c: parameters[3],
+                d: parameters[4],
+            }),
+            _ => FastCurve::Other(curve.clone()),
+        })
+    }
+
+    fn apply(&self, values: &mut [f32]) {
+        match self {
+            FastCurve::Parametric(curve) => {
+                for value in values.iter_mut() {
+                    *value = curve.apply_approx(*value);
+                }
+            }
+            FastCurve::Other(curve) => {
+                for value in values.iter_mut() {
+                    *value = curve.apply_approx(*value);
+                }
+            }
+        }
+    }
+
+    fn apply_inverse(&self, values: &mut [f32]) {
+        match self {
+            FastCurve::Parametric(curve) => {
+                for value in values.iter_mut() {
+                    *value = curve.apply_inverse_approx(*value);
+                }
+            }
+            FastCurve::Other(curve) => {
+                for value in values.iter_mut() {
+                    *value = curve.apply_inverse_approx(*value);
+                }
+            }
+        }
+    }
+}
+
+/// A component type with a single precision fast path.
+trait FastComponent: Copy {
+    fn from_f32(value: f32) -> Self;
+}
+
+impl FastComponent for f32 {
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl FastComponent for u8 {
+    fn from_f32(value: f32) -> Self {
+        // Truncating after adding 0.5 rounds, since the value is clamped to be non-negative.
+        (value.clamp(0.0, 1.0) * 255.0 + 0.5) as u8
+    }
+}
+
+impl FastComponent for u16 {
+    fn from_f32(value: f32) -> Self {
+        (value.clamp(0.0, 1.0) * 65535.0 + 0.5) as u16
+    }
+}
+
+/// A color of an RGB color space with single precision components, such as a pixel of a float
+/// image. Conversions use the approximate tone response curves, like `FastConverter`.
+#[derive(Clone, Copy, Debug)]
+pub struct RGBf32<S> {
+    pub components: [f32; 3],
+    color_space: PhantomData<S>,
+}
+
+/// An sRGB color with single precision components.
+pub type SRGBf32 = RGBf32<SRGB>;
+
+/// A Display P3 color with single precision components.
+pub type DisplayP3f32 = RGBf32<DisplayP3>;
+
+impl<S> RGBf32<S>
+where
+    S: RGBColorSpace,
+{
+    pub fn new(components: [f32; 3]) -> Self {
+        RGBf32 {
+            components,
+            color_space: PhantomData,
+        }
+    }
+
+    /// Round the components of a double precision color.
+    pub fn from_color(color: &S) -> Self {
+        let v = color.to_vector3();
+        Self::new([v[0] as f32, v[1] as f32, v[2] as f32])
+    }
+
+    /// Widen the components to a double precision color.
+    pub fn to_color(&self) -> S {
+        S::from_vector3(Vector3::from_iterator(
+            self.components.iter().map(|c| f64::from(*c)),
+        ))
+    }
+
+    pub fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
+        let transform = S::matrix_trc_transform();
+        let linear = transform.from_ciexyz_matrix() * ciexyz.to_vector3();
+        let curves = [
+            transform.red_trc(),
+            transform.green_trc(),
+            transform.blue_trc(),
+        ];
+        let mut components = [0.0; 3];
+        for ((component, curve), value) in components.iter_mut().zip(curves.iter()).zip(&linear) {
+            *component = curve.apply_inverse_approx(*value as f32);
+        }
+        Self::new(components)
+    }
+
+    pub fn to_ciexyz(&self) -> CIEXYZ {
+        let transform = S::matrix_trc_transform();
+        let curves = [
+            transform.red_trc(),
+            transform.green_trc(),
+            transform.blue_trc(),
+        ];
+        let linear = Vector3::from_iterator(
+            curves
+                .iter()
+                .zip(&self.components)
+                .map(|(curve, component)| f64::from(curve.apply_approx(*component))),
+        );
+        CIEXYZ::from_vector3(transform.to_ciexyz_matrix() * linear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::*;
+    use crate::colors::{Converter, ParametricCurveType3};
+
+    fn srgb_curve() -> ParametricCurveType3 {
+        ParametricCurveType3 {
+            g: 2.4,
+            a: 1.0 / 1.055,
+            b: 0.055 / 1.055,
+            c: 1.0 / 12.92,
+            d: 0.04045,
+        }
+    }
+
+    #[test]
+    fn test_curve_approx_matches_reference() {
+        let curve = srgb_curve();
+        for i in 0..=10000 {
+            let x = f64::from(i) / 10000.0;
+            let forward = f64::from(curve.apply_approx(x as f32));
+            assert!((forward - curve.apply(x)).abs() < 1e-5, "{}", x);
+            let inverse = f64::from(curve.apply_inverse_approx(x as f32));
+            assert!((inverse - curve.apply_inverse(x)).abs() < 1e-5, "{}", x);
+        }
+    }
+
+    #[test]
+    fn test_srgb_decoding_table() {
+        let converter = FastConverter::<SRGB, DisplayP3>::new();
+        for (i, value) in converter.decoding_tables[0].iter().enumerate() {
+            let color = SRGB::from_vector3(Vector3::repeat(i as f64 / 255.0));
+            assert!((f64::from(*value) - color.to_linear()[0]).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_u8_matches_reference() -> Result<(), Error> {
+        let fast = FastConverter::<SRGB, DisplayP3>::new();
+        let reference = Converter::<SRGB, DisplayP3>::new();
+        let mut pixels = Vec::new();
+        for r in (0..=255).step_by(5) {
+            for g in (0..=255).step_by(5) {
+                for b in (0..=255).step_by(5) {
+                    pixels.extend_from_slice(&[r, g, b, 128]);
+                }
+            }
+        }
+        let mut expected = pixels.clone();
+        reference.convert_pixels_in_place(&mut expected, PixelLayout::Rgba)?;
+        fast.convert_u8(&mut pixels, PixelLayout::Rgba)?;
+        for (actual, expected) in pixels.iter().zip(expected.iter()) {
+            assert!((i32::from(*actual) - i32::from(*expected)).abs() <= 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_u16_matches_reference() -> Result<(), Error> {
+        let fast = FastConverter::<DisplayP3, SRGB>::new();
+        let reference = Converter::<DisplayP3, SRGB>::new();
+        let mut pixels: Vec<u16> = (0..=65535).step_by(97).collect();
+        pixels.truncate(pixels.len() / 3 * 3);
+        let mut expected = pixels.clone();
+        reference.convert_pixels_in_place(&mut expected, PixelLayout::Rgb)?;
+        fast.convert_u16(&mut pixels, PixelLayout::Rgb)?;
+        for (actual, expected) in pixels.iter().zip(expected.iter()) {
+            assert!((i32::from(*actual) - i32::from(*expected)).abs() <= 1);
+        }
+        assert!(fast.convert_u16(&mut [0; 4], PixelLayout::Rgb).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_f32_colors_match_reference() -> Result<(), Error> {
+        let fast = FastConverter::<SRGB, DisplayP3>::new();
+        for hex in &["ff0000", "00ff00", "0000ff", "808080", "ffffff", "123456"] {
+            let srgb = SRGB::from_hex(hex)?;
+            let color = SRGBf32::from_color(&srgb);
+            let xyz = color.to_ciexyz().to_vector3();
+            assert!((xyz - srgb.to_ciexyz().to_vector3()).amax() < 1e-5);
+            let round_trip = SRGBf32::from_ciexyz(&srgb.to_ciexyz());
+            assert!((round_trip.to_color().to_vector3() - srgb.to_vector3()).amax() < 1e-5);
+
+            let expected = DisplayP3::from_color(&srgb).to_vector3();
+            let converted = fast.convert(&color).to_color().to_vector3();
+            assert!((converted - expected).amax() < 1e-5);
+            let via_ciexyz = DisplayP3f32::from_ciexyz(&color.to_ciexyz());
+            assert!((via_ciexyz.to_color().to_vector3() - expected).amax() < 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_f32_matches_reference() -> Result<(), Error> {
+        let fast = FastConverter::<DisplayP3, SRGB>::new();
+        let reference = Converter::<DisplayP3, SRGB>::new();
+        let mut pixels: Vec<f32> = (0..300).map(|i| i as f32 / 299.0).collect();
+        let mut expected = pixels.clone();
+        reference.convert_pixels_in_place(&mut expected, PixelLayout::Rgb)?;
+        fast.convert_f32(&mut pixels, PixelLayout::Rgb)?;
+        for (actual, expected) in pixels.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 1.0 / 65535.0);
+        }
+        assert!(fast.convert_f32(&mut [0.0; 4], PixelLayout::Rgb).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use super::{Color, CIELUV, OKLAB};
+
+/// A perceptual color space that can be expressed in cylindrical lightness, chroma, and hue
+/// coordinates.
+///
+/// Lightness is normalized to [0, 1] and hue is in radians in both spaces, but chroma is on the
+/// native scale of each space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LchSpace {
+    /// CIE LCh(uv), the cylindrical form of CIELUV.
+    CIELUV,
+    /// The cylindrical form of Oklab.
+    OKLCH,
+}
+
+impl LchSpace {
+    /// Convert a color to lightness, chroma, and hue in this space.
+    pub fn to_lch<C>(self, color: &C) -> (f64, f64, f64)
+    where
+        C: Color,
+    {
+        match self {
+            LchSpace::CIELUV => color.to_color::<CIELUV>().as_lch(),
+            LchSpace::OKLCH => color.to_color::<OKLAB>().as_lch(),
+        }
+    }
+
+    /// Convert lightness, chroma, and hue in this space to a color.
+    pub fn from_lch<C>(self, l: f64, c: f64, h: f64) -> C
+    where
+        C: Color,
+    {
+        match self {
+            LchSpace::CIELUV => CIELUV::from_lch(l, c, h).to_color(),
+            LchSpace::OKLCH => OKLAB::from_lch(l, c, h).to_color(),
+        }
+    }
+}
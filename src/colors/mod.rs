@@ -1,14 +1,34 @@
+mod cie1960ucs;
+mod cie1976ucs;
+mod cielab;
 mod cieluv;
+mod cieuvw;
 mod ciexyy;
 mod ciexyz;
 mod color;
+mod converter;
 mod display_p3;
+mod fast;
+mod lch;
+mod oklab;
 mod srgb;
 mod utils;
 
+pub use cie1960ucs::CIE1960UCS;
+pub use cie1976ucs::CIE1976UCS;
+pub use cielab::CIELAB;
 pub use cieluv::CIELUV;
+pub use cieuvw::CIEUVW;
 pub use ciexyy::CIEXYY;
 pub use ciexyz::{get_d65_ciexyz, CIEXYZ};
 pub use color::Color;
+pub use converter::{Converter, RGBColorSpace};
 pub use display_p3::DisplayP3;
+pub use fast::{DisplayP3f32, FastConverter, RGBf32, SRGBf32};
+pub use lch::LchSpace;
+pub use oklab::OKLAB;
 pub use srgb::SRGB;
+pub use utils::{
+    MatrixTRCTransform, ParametricCurveType0, ParametricCurveType1, ParametricCurveType2,
+    ParametricCurveType3, ParametricCurveType4, SampledCurve, ToneResponseCurve,
+};
@@ -0,0 +1,125 @@
+use nalgebra::{Matrix3, Vector3};
+
+use super::{Color, CIEXYZ};
+
+/// A color represented in the Oklab color space.
+///
+/// Oklab is a perceptual color space designed by Björn Ottosson to predict lightness, chroma, and
+/// hue more uniformly than CIELAB, particularly for blue hues. It assumes a D65 white point.
+///
+/// See https://bottosson.github.io/posts/oklab/ for more information.
+///
+/// L is already normalized to [0, 1], so unlike CIELAB and CIELUV no rescaling is needed.
+#[derive(Clone, Copy, Debug)]
+pub struct OKLAB {
+    vec: Vector3<f64>, // Components are L, a, and b
+}
+
+impl OKLAB {
+    pub fn chroma(&self) -> f64 {
+        let a = self.vec[1];
+        let b = self.vec[2];
+        (a.powf(2.0) + b.powf(2.0)).sqrt()
+    }
+
+    // In radians
+    pub fn hue(&self) -> f64 {
+        let a = self.vec[1];
+        let b = self.vec[2];
+        b.atan2(a)
+    }
+
+    /// Return the cylindrical OKLCH coordinates of this color.
+    pub fn as_lch(&self) -> (f64, f64, f64) {
+        (self.vec[0], self.chroma(), self.hue())
+    }
+
+    pub fn from_lch(l: f64, c: f64, h: f64) -> Self {
+        Self::from_vector3(Vector3::new(l, c * h.cos(), c * h.sin()))
+    }
+}
+
+#[rustfmt::skip]
+fn xyz_to_lms_matrix() -> Matrix3<f64> {
+    Matrix3::new(
+        0.8189330101, 0.3618667424, -0.1288597137,
+        0.0329845436, 0.9293118715,  0.0361456387,
+        0.0482003018, 0.2643662691,  0.6338517070,
+    )
+}
+
+#[rustfmt::skip]
+fn lms_to_lab_matrix() -> Matrix3<f64> {
+    Matrix3::new(
+        0.2104542553,  0.7936177850, -0.0040720468,
+        1.9779984951, -2.4285922050,  0.4505937099,
+        0.0259040371,  0.7827717662, -0.8086757660,
+    )
+}
+
+/// The inverse of `xyz_to_lms_matrix`, which agrees with the one published with Oklab to its ten
+/// digits.
+#[rustfmt::skip]
+fn lms_to_xyz_matrix() -> Matrix3<f64> {
+    Matrix3::new(
+         1.2270138511035211, -0.5577999806518222,  0.2812561489664678,
+        -0.0405801784232806,  1.11225686961683,   -0.0716766786656012,
+        -0.0763812845057069, -0.4214819784180127,  1.5861632204407947,
+    )
+}
+
+/// The inverse of `lms_to_lab_matrix`.
+#[rustfmt::skip]
+fn lab_to_lms_matrix() -> Matrix3<f64> {
+    Matrix3::new(
+        0.9999999984505198,  0.3963377921737679,  0.2158037580607588,
+        1.0000000088817609, -0.1055613423236564, -0.0638541747717059,
+        1.0000000546724108, -0.0894841820949658, -1.2914855378640917,
+    )
+}
+
+impl Color for OKLAB {
+    fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
+        let lms = xyz_to_lms_matrix() * ciexyz.to_vector3();
+        Self::from_vector3(lms_to_lab_matrix() * lms.map(f64::cbrt))
+    }
+
+    fn to_ciexyz(&self) -> CIEXYZ {
+        let lms = (lab_to_lms_matrix() * self.vec).map(|x| x.powf(3.0));
+        CIEXYZ::from_vector3(lms_to_xyz_matrix() * lms)
+    }
+
+    fn from_vector3(vec: Vector3<f64>) -> Self {
+        OKLAB { vec }
+    }
+
+    fn to_vector3(&self) -> Vector3<f64> {
+        self.vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::get_d65_ciexyz;
+
+    #[test]
+    fn test_oklab_white() {
+        let white = OKLAB::from_ciexyz(&get_d65_ciexyz());
+        assert!((white.to_vector3() - Vector3::new(1.0, 0.0, 0.0)).magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn test_inverse_matrices() {
+        let identity = Matrix3::identity();
+        assert!((lms_to_xyz_matrix() * xyz_to_lms_matrix() - identity).amax() < 1e-14);
+        assert!((lab_to_lms_matrix() * lms_to_lab_matrix() - identity).amax() < 1e-14);
+    }
+
+    #[test]
+    fn test_oklab_round_trip() {
+        let oklab = OKLAB::from_lch(0.6, 0.15, 2.0);
+        let round_trip = OKLAB::from_ciexyz(&oklab.to_ciexyz());
+        assert!((oklab.to_vector3() - round_trip.to_vector3()).magnitude() < 1e-12);
+    }
+}
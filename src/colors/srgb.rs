@@ -1,7 +1,9 @@
+use std::sync::{Arc, OnceLock};
+
 use nalgebra::{Matrix3, Vector3};
 
 use super::utils::{MatrixTRCTransform, ParametricCurveType3};
-use super::{Color, CIEXYZ};
+use super::{Color, RGBColorSpace, CIEXYZ};
 
 /// A color represented in the sRGB color space.
 ///
@@ -15,6 +17,26 @@ pub struct SRGB {
     vec: Vector3<f64>,
 }
 
+impl SRGB {
+    /// Return the linear-light RGB components of this color, i.e. with the tone response curve
+    /// removed.
+    pub fn to_linear(&self) -> Vector3<f64> {
+        self.vec.map(|x| TONE_RESPONSE_CURVE.apply(x))
+    }
+
+    /// Create a color from linear-light RGB components.
+    pub fn from_linear(linear: Vector3<f64>) -> Self {
+        Self::from_vector3(linear.map(|x| TONE_RESPONSE_CURVE.apply_inverse(x)))
+    }
+}
+
+impl RGBColorSpace for SRGB {
+    /// The transform between sRGB and CIE XYZ, as defined by the ICC sRGB v4 profile.
+    fn matrix_trc_transform() -> &'static MatrixTRCTransform {
+        matrix_trc_transform()
+    }
+}
+
 impl Color for SRGB {
     fn from_ciexyz(ciexyz: &CIEXYZ) -> Self {
         let in_vec = ciexyz.to_vector3();
@@ -64,12 +86,17 @@ fn chromatic_adaptation_matrix() -> Matrix3<f64> {
     )
 }
 
-fn matrix_trc_transform() -> MatrixTRCTransform {
-    MatrixTRCTransform {
-        matrix: transform_matrix(),
-        red_trc: TONE_RESPONSE_CURVE,
-        green_trc: TONE_RESPONSE_CURVE,
-        blue_trc: TONE_RESPONSE_CURVE,
-        chromatic_adaptation_matrix: chromatic_adaptation_matrix(),
-    }
+fn matrix_trc_transform() -> &'static MatrixTRCTransform {
+    static TRANSFORM: OnceLock<MatrixTRCTransform> = OnceLock::new();
+    TRANSFORM.get_or_init(|| {
+        let curve = Arc::new(TONE_RESPONSE_CURVE);
+        MatrixTRCTransform::new(
+            transform_matrix(),
+            curve.clone(),
+            curve.clone(),
+            curve,
+            chromatic_adaptation_matrix(),
+        )
+        .expect("The sRGB matrices are invertible.")
+    })
 }
@@ -1,5 +1,188 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
 use nalgebra::{Matrix3, Vector3};
 
+use super::{Color, CIEXYZ};
+
+/// A tone response curve, which applies a nonlinear transformation to a component of a color.
+///
+/// `apply` maps from the device color space towards the profile connection space (CIE XYZ), and
+/// `apply_inverse` maps in the opposite direction.
+pub trait ToneResponseCurve: Debug + Send + Sync {
+    /// Apply the curve to a value.
+    fn apply(&self, x: f64) -> f64;
+
+    /// Apply the inverse of the curve to a value.
+    fn apply_inverse(&self, y: f64) -> f64;
+
+    /// The ICC parametricCurveType function type and parameters, if the curve is one of them.
+    fn parametric(&self) -> Option<(u16, Vec<f64>)> {
+        None
+    }
+
+    /// The evenly spaced samples of the curve, if it is tabulated.
+    fn samples(&self) -> Option<&[f64]> {
+        None
+    }
+
+    /// Apply the curve to a single precision value, possibly approximately, for fast paths.
+    fn apply_approx(&self, x: f32) -> f32 {
+        self.apply(f64::from(x)) as f32
+    }
+
+    /// Apply the inverse of the curve to a single precision value, possibly approximately, for
+    /// fast paths.
+    fn apply_inverse_approx(&self, y: f32) -> f32 {
+        self.apply_inverse(f64::from(y)) as f32
+    }
+}
+
+/// Least squares fit of log2(1 + t) / t for t in [0, 1], with an error below 2e-6.
+const LOG2_COEFFICIENTS: [f32; 7] = [
+    1.442_693_3,
+    -0.721_162_7,
+    0.477_705_9,
+    -0.339_247_8,
+    0.215_588_5,
+    -0.096_066_25,
+    0.020_490_35,
+];
+
+/// Least squares fit of 2^f for f in [0, 1], with a relative error below 1e-7.
+const EXP2_COEFFICIENTS: [f32; 6] = [
+    0.999_999_9,
+    0.693_153,
+    0.240_154_5,
+    0.055_823_6,
+    0.008_992_59,
+    0.001_876_231,
+];
+
+fn polynomial(coefficients: &[f32], x: f32) -> f32 {
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * x + c)
+}
+
+/// Approximate x^p for positive x as 2^(p log2(x)), evaluating log2 and exp2 from the exponent
+/// bits and a polynomial of the mantissa. Non-positive x is mapped to 0.
+///
+/// There are no branches or calls, so that loops over blocks of values can be vectorized.
+fn pow_approx(x: f32, p: f32) -> f32 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as i32 - 127;
+    let t = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000) - 1.0;
+    // Below -127 the power underflows to 0, and from 128 it overflows to infinity.
+    let y = (p * (exponent as f32 + t * polynomial(&LOG2_COEFFICIENTS, t))).clamp(-127.0, 128.0);
+    // The floor of y, without calling floorf, which is not vectorized on every target.
+    let truncated = y as i32;
+    let integer = truncated - i32::from(y < truncated as f32);
+    let scale = f32::from_bits(((integer + 127) as u32) << 23);
+    let power = scale * polynomial(&EXP2_COEFFICIENTS, y - integer as f32);
+    if x > 0.0 {
+        power
+    } else {
+        0.0
+    }
+}
+
+/// ICC parametricCurveType 0, a pure gamma function:
+///
+/// y = x^g
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParametricCurveType0 {
+    pub g: f64,
+}
+
+impl ToneResponseCurve for ParametricCurveType0 {
+    fn apply(&self, x: f64) -> f64 {
+        x.powf(self.g)
+    }
+
+    /// x = y^(1/g)
+    fn apply_inverse(&self, y: f64) -> f64 {
+        y.powf(1.0 / self.g)
+    }
+
+    fn parametric(&self) -> Option<(u16, Vec<f64>)> {
+        Some((0, vec![self.g]))
+    }
+}
+
+/// ICC parametricCurveType 1 (CIE 122-1966):
+///
+/// y = (a*x + b)^g  for (x >= -b/a)
+/// y = 0            for (x < -b/a)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParametricCurveType1 {
+    pub g: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl ToneResponseCurve for ParametricCurveType1 {
+    fn apply(&self, x: f64) -> f64 {
+        let ParametricCurveType1 { g, a, b } = *self;
+        if x >= -b / a {
+            (a * x + b).powf(g)
+        } else {
+            0.0
+        }
+    }
+
+    /// x = (y^(1/g) - b)/a  for (y > 0)
+    /// x = -b/a             for (y <= 0)
+    fn apply_inverse(&self, y: f64) -> f64 {
+        let ParametricCurveType1 { g, a, b } = *self;
+        if y > 0.0 {
+            (y.powf(1.0 / g) - b) / a
+        } else {
+            -b / a
+        }
+    }
+
+    fn parametric(&self) -> Option<(u16, Vec<f64>)> {
+        Some((1, vec![self.g, self.a, self.b]))
+    }
+}
+
+/// ICC parametricCurveType 2 (IEC 61966-3):
+///
+/// y = (a*x + b)^g + c  for (x >= -b/a)
+/// y = c                for (x < -b/a)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParametricCurveType2 {
+    pub g: f64,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl ToneResponseCurve for ParametricCurveType2 {
+    fn apply(&self, x: f64) -> f64 {
+        let ParametricCurveType2 { g, a, b, c } = *self;
+        if x >= -b / a {
+            (a * x + b).powf(g) + c
+        } else {
+            c
+        }
+    }
+
+    /// x = ((y - c)^(1/g) - b)/a  for (y > c)
+    /// x = -b/a                   for (y <= c)
+    fn apply_inverse(&self, y: f64) -> f64 {
+        let ParametricCurveType2 { g, a, b, c } = *self;
+        if y > c {
+            ((y - c).powf(1.0 / g) - b) / a
+        } else {
+            -b / a
+        }
+    }
+
+    fn parametric(&self) -> Option<(u16, Vec<f64>)> {
+        Some((2, vec![self.g, self.a, self.b, self.c]))
+    }
+}
+
 /// ICC parametricCurveType 3
 ///
 /// parametricCurveType 3 is a piecewise, continuous function of the form:
@@ -12,6 +195,7 @@ use nalgebra::{Matrix3, Vector3};
 /// component of a color. This function is used to from the device color space to the profile
 /// connection space (CIE XYZ). The inverse function should be used when mapping in the opposite
 /// direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ParametricCurveType3 {
     pub g: f64,
     pub a: f64,
@@ -43,37 +227,312 @@ impl ParametricCurveType3 {
             y / c
         }
     }
+
+    /// Apply the parametric curve in single precision, with a relative error of about 1e-5.
+    ///
+    /// Both pieces of the curve are evaluated and one is selected without branching.
+    pub fn apply_approx(&self, x: f32) -> f32 {
+        let ParametricCurveType3 { g, a, b, c, d } = *self;
+        let power = pow_approx(a as f32 * x + b as f32, g as f32);
+        let linear = c as f32 * x;
+        if x >= d as f32 {
+            power
+        } else {
+            linear
+        }
+    }
+
+    /// Apply the inverse function in single precision, with a relative error of about 1e-5,
+    /// which is well below the resolution of 16-bit components.
+    pub fn apply_inverse_approx(&self, y: f32) -> f32 {
+        let ParametricCurveType3 { g, a, b, c, d } = *self;
+        let power = (pow_approx(y, (1.0 / g) as f32) - b as f32) / a as f32;
+        let linear = y / c as f32;
+        if y >= (c * d) as f32 {
+            power
+        } else {
+            linear
+        }
+    }
 }
 
+impl ToneResponseCurve for ParametricCurveType3 {
+    fn apply(&self, x: f64) -> f64 {
+        ParametricCurveType3::apply(self, x)
+    }
+
+    fn apply_inverse(&self, y: f64) -> f64 {
+        ParametricCurveType3::apply_inverse(self, y)
+    }
+
+    fn apply_approx(&self, x: f32) -> f32 {
+        ParametricCurveType3::apply_approx(self, x)
+    }
+
+    fn apply_inverse_approx(&self, y: f32) -> f32 {
+        ParametricCurveType3::apply_inverse_approx(self, y)
+    }
+
+    fn parametric(&self) -> Option<(u16, Vec<f64>)> {
+        Some((3, vec![self.g, self.a, self.b, self.c, self.d]))
+    }
+}
+
+/// ICC parametricCurveType 4:
+///
+/// y = (a*x + b)^g + e  for (x >= d)
+/// y = c*x + f          for (x < d)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParametricCurveType4 {
+    pub g: f64,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl ToneResponseCurve for ParametricCurveType4 {
+    fn apply(&self, x: f64) -> f64 {
+        let ParametricCurveType4 {
+            g,
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+        } = *self;
+        if x >= d {
+            (a * x + b).powf(g) + e
+        } else {
+            c * x + f
+        }
+    }
+
+    /// x = ((y - e)^(1/g) - b)/a  for (y >= (a*d + b)^g + e)
+    /// x = (y - f)/c              for (y < (a*d + b)^g + e)
+    fn apply_inverse(&self, y: f64) -> f64 {
+        let ParametricCurveType4 {
+            g,
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+        } = *self;
+        if y >= (a * d + b).powf(g) + e {
+            ((y - e).powf(1.0 / g) - b) / a
+        } else if c == 0.0 {
+            d
+        } else {
+            (y - f) / c
+        }
+    }
+
+    fn parametric(&self) -> Option<(u16, Vec<f64>)> {
+        let ParametricCurveType4 {
+            g,
+            a,
+            b,
+            c,
+            d,
+            e,
+            f,
+        } = *self;
+        Some((4, vec![g, a, b, c, d, e, f]))
+    }
+}
+
+/// A tone response curve sampled at evenly spaced inputs over [0, 1], as in an ICC curveType
+/// with more than one entry. Values between samples are linearly interpolated.
+///
+/// The samples are assumed to be monotonic. The inverse is found by searching for the pair of
+/// samples that brackets the value, and inputs outside of the range of the samples are clamped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampledCurve {
+    samples: Vec<f64>,
+}
+
+impl SampledCurve {
+    /// Create a curve from at least two samples.
+    pub fn new(samples: Vec<f64>) -> Option<Self> {
+        if samples.len() < 2 || samples.iter().any(|v| !v.is_finite()) {
+            return None;
+        }
+        Some(SampledCurve { samples })
+    }
+
+    fn step(&self) -> f64 {
+        1.0 / (self.samples.len() - 1) as f64
+    }
+}
+
+impl ToneResponseCurve for SampledCurve {
+    fn apply(&self, x: f64) -> f64 {
+        let position = x.clamp(0.0, 1.0) * (self.samples.len() - 1) as f64;
+        let i = (position.floor() as usize).min(self.samples.len() - 2);
+        let t = position - i as f64;
+        self.samples[i] * (1.0 - t) + self.samples[i + 1] * t
+    }
+
+    fn apply_inverse(&self, y: f64) -> f64 {
+        let samples = &self.samples;
+        let last = samples.len() - 1;
+        let increasing = samples[last] >= samples[0];
+        // The index of the first sample at or past y.
+        let i = if increasing {
+            samples.partition_point(|v| *v < y)
+        } else {
+            samples.partition_point(|v| *v > y)
+        };
+        if i == 0 {
+            return 0.0;
+        }
+        if i > last {
+            return 1.0;
+        }
+        let (low, high) = (samples[i - 1], samples[i]);
+        let t = if high == low {
+            0.0
+        } else {
+            (y - low) / (high - low)
+        };
+        (i as f64 - 1.0 + t) * self.step()
+    }
+
+    fn samples(&self) -> Option<&[f64]> {
+        Some(&self.samples)
+    }
+}
+
+/// A transform between a device RGB space and CIE XYZ, as defined by an ICC matrix/TRC profile.
+///
+/// The matrices mapping between linear device RGB and CIE XYZ are computed once, when the
+/// transform is created, rather than on every conversion.
+#[derive(Clone, Debug)]
 pub struct MatrixTRCTransform {
-    pub matrix: Matrix3<f64>, // Mapping from device space to PCS XYZ, sans chromatic adaptation or tone response curves
-    pub red_trc: ParametricCurveType3,
-    pub green_trc: ParametricCurveType3,
-    pub blue_trc: ParametricCurveType3,
-    pub chromatic_adaptation_matrix: Matrix3<f64>, // Chromatically adapt white point in PCS XYZ
+    matrix: Matrix3<f64>, // Mapping from device space to PCS XYZ, sans chromatic adaptation or tone response curves
+    red_trc: Arc<dyn ToneResponseCurve>,
+    green_trc: Arc<dyn ToneResponseCurve>,
+    blue_trc: Arc<dyn ToneResponseCurve>,
+    chromatic_adaptation_matrix: Matrix3<f64>, // Chromatically adapt white point in PCS XYZ
+    matrix_inverse: Matrix3<f64>,
+    to_ciexyz_matrix: Matrix3<f64>,
+    from_ciexyz_matrix: Matrix3<f64>,
 }
 
 impl MatrixTRCTransform {
+    /// Create a transform from the matrix mapping linear device RGB to PCS XYZ, the tone response
+    /// curves of each component, and the matrix adapting the white point of the device to the
+    /// PCS.
+    ///
+    /// Returns `None` if either matrix is not invertible.
+    pub fn new(
+        matrix: Matrix3<f64>,
+        red_trc: Arc<dyn ToneResponseCurve>,
+        green_trc: Arc<dyn ToneResponseCurve>,
+        blue_trc: Arc<dyn ToneResponseCurve>,
+        chromatic_adaptation_matrix: Matrix3<f64>,
+    ) -> Option<Self> {
+        let matrix_inverse = matrix.try_inverse()?;
+        let chromatic_adaptation_inverse = chromatic_adaptation_matrix.try_inverse()?;
+        Some(MatrixTRCTransform {
+            matrix,
+            red_trc,
+            green_trc,
+            blue_trc,
+            chromatic_adaptation_matrix,
+            matrix_inverse,
+            to_ciexyz_matrix: chromatic_adaptation_inverse * matrix,
+            from_ciexyz_matrix: matrix_inverse * chromatic_adaptation_matrix,
+        })
+    }
+
+    /// Replace the tone response curves, keeping the matrices.
+    pub fn with_trcs(
+        self,
+        red_trc: Arc<dyn ToneResponseCurve>,
+        green_trc: Arc<dyn ToneResponseCurve>,
+        blue_trc: Arc<dyn ToneResponseCurve>,
+    ) -> Self {
+        MatrixTRCTransform {
+            red_trc,
+            green_trc,
+            blue_trc,
+            ..self
+        }
+    }
+
+    /// The matrix mapping linear device RGB to PCS XYZ.
+    pub fn matrix(&self) -> &Matrix3<f64> {
+        &self.matrix
+    }
+
+    /// The inverse of `matrix`, mapping PCS XYZ to linear device RGB.
+    pub fn matrix_inverse(&self) -> &Matrix3<f64> {
+        &self.matrix_inverse
+    }
+
+    pub fn red_trc(&self) -> &Arc<dyn ToneResponseCurve> {
+        &self.red_trc
+    }
+
+    pub fn green_trc(&self) -> &Arc<dyn ToneResponseCurve> {
+        &self.green_trc
+    }
+
+    pub fn blue_trc(&self) -> &Arc<dyn ToneResponseCurve> {
+        &self.blue_trc
+    }
+
+    /// The matrix adapting the white point of the device to the PCS.
+    pub fn chromatic_adaptation_matrix(&self) -> &Matrix3<f64> {
+        &self.chromatic_adaptation_matrix
+    }
+
+    /// The matrix mapping linear device RGB to CIE XYZ, including chromatic adaptation.
+    pub fn to_ciexyz_matrix(&self) -> &Matrix3<f64> {
+        &self.to_ciexyz_matrix
+    }
+
+    /// The matrix mapping CIE XYZ to linear device RGB, including chromatic adaptation.
+    pub fn from_ciexyz_matrix(&self) -> &Matrix3<f64> {
+        &self.from_ciexyz_matrix
+    }
+
     /// Transform from device space to PCS XYZ
     pub fn to_profile_connection_space(&self, v: Vector3<f64>) -> Vector3<f64> {
-        let chromatic_adaptation = self
-            .chromatic_adaptation_matrix
-            .try_inverse()
-            .expect("Matrix is not invertible.");
-
-        chromatic_adaptation * self.matrix * self.apply_trc(v)
+        self.to_ciexyz_matrix * self.apply_trc(v)
     }
 
     /// Transform from PCS XYZ to device space
     pub fn to_device_space(&self, v: Vector3<f64>) -> Vector3<f64> {
-        let linear_transform = self
-            .matrix
-            .try_inverse()
-            .expect("Matrix is not invertible.");
-        self.apply_trc_inverse(linear_transform * self.chromatic_adaptation_matrix * v)
+        self.apply_trc_inverse(self.from_ciexyz_matrix * v)
+    }
+
+    /// Convert device RGB components to a color.
+    pub fn to_color<C>(&self, device: Vector3<f64>) -> C
+    where
+        C: Color,
+    {
+        C::from_ciexyz(&CIEXYZ::from_vector3(
+            self.to_profile_connection_space(device),
+        ))
+    }
+
+    /// Convert a color to device RGB components.
+    pub fn from_color<C>(&self, color: &C) -> Vector3<f64>
+    where
+        C: Color,
+    {
+        self.to_device_space(color.to_ciexyz().to_vector3())
     }
 
-    fn apply_trc(&self, v: Vector3<f64>) -> Vector3<f64> {
+    /// Apply the tone response curves, converting device RGB to linear RGB.
+    pub fn apply_trc(&self, v: Vector3<f64>) -> Vector3<f64> {
         let r = v[0];
         let g = v[1];
         let b = v[2];
@@ -84,7 +543,8 @@ impl MatrixTRCTransform {
         )
     }
 
-    fn apply_trc_inverse(&self, v: Vector3<f64>) -> Vector3<f64> {
+    /// Apply the inverse tone response curves, converting linear RGB to device RGB.
+    pub fn apply_trc_inverse(&self, v: Vector3<f64>) -> Vector3<f64> {
         let r = v[0];
         let g = v[1];
         let b = v[2];
@@ -95,3 +555,107 @@ impl MatrixTRCTransform {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip(curve: &dyn ToneResponseCurve) {
+        for i in 0..=20 {
+            let x = f64::from(i) / 20.0;
+            let y = curve.apply(x);
+            assert!(
+                (curve.apply_inverse(y) - x).abs() < 1e-9,
+                "{:?} {}",
+                curve,
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn test_pow_approx() {
+        for p in [0.3, 1.0 / 2.4, 1.0, 2.2, 2.4].iter() {
+            for i in 1..=20000 {
+                let x = i as f32 / 10000.0;
+                let expected = f64::from(x).powf(f64::from(*p));
+                let relative = (f64::from(pow_approx(x, *p)) - expected) / expected;
+                assert!(relative.abs() < 2e-5, "{}^{}", x, p);
+            }
+        }
+        assert_eq!(pow_approx(0.0, 2.4), 0.0);
+        assert_eq!(pow_approx(-1.0, 2.4), 0.0);
+        assert_eq!(pow_approx(1e-30, 10.0), 0.0);
+        assert_eq!(pow_approx(f32::NAN, 2.4), 0.0);
+        assert_eq!(pow_approx(1e30, 10.0), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_parametric_curves_round_trip() {
+        assert_round_trip(&ParametricCurveType0 { g: 2.2 });
+        assert_round_trip(&ParametricCurveType1 {
+            g: 2.4,
+            a: 1.0,
+            b: 0.0,
+        });
+        assert_round_trip(&ParametricCurveType2 {
+            g: 2.2,
+            a: 0.9,
+            b: 0.1,
+            c: 0.05,
+        });
+        assert_round_trip(&ParametricCurveType3 {
+            g: 2.4,
+            a: 1.0 / 1.055,
+            b: 0.055 / 1.055,
+            c: 1.0 / 12.92,
+            d: 0.04045,
+        });
+        assert_round_trip(&ParametricCurveType4 {
+            g: 2.4,
+            a: 1.0 / 1.055,
+            b: 0.055 / 1.055,
+            c: 1.0 / 12.92,
+            d: 0.04045,
+            e: 0.01,
+            f: 0.01,
+        });
+    }
+
+    #[test]
+    fn test_parametric_curve_offsets() {
+        let type1 = ParametricCurveType1 {
+            g: 2.0,
+            a: 2.0,
+            b: -0.5,
+        };
+        assert_eq!(type1.apply(0.1), 0.0);
+        assert!((type1.apply(0.5) - 0.25).abs() < 1e-12);
+        let type2 = ParametricCurveType2 {
+            g: 2.0,
+            a: 2.0,
+            b: -0.5,
+            c: 0.1,
+        };
+        assert_eq!(type2.apply(0.1), 0.1);
+        assert!((type2.apply_inverse(0.35) - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sampled_curve() {
+        assert!(SampledCurve::new(vec![0.5]).is_none());
+        let samples = (0..=1024)
+            .map(|i| (f64::from(i) / 1024.0).powf(2.2))
+            .collect();
+        let curve = SampledCurve::new(samples).expect("There are enough samples.");
+        assert!((curve.apply(0.5) - 0.5_f64.powf(2.2)).abs() < 1e-5);
+        assert_eq!(curve.apply(1.5), 1.0);
+        assert_round_trip(&curve);
+
+        let decreasing = SampledCurve::new(vec![1.0, 0.5, 0.0]).expect("There are enough samples.");
+        assert!((decreasing.apply(0.25) - 0.75).abs() < 1e-12);
+        assert_round_trip(&decreasing);
+        assert_eq!(decreasing.apply_inverse(2.0), 0.0);
+        assert_eq!(decreasing.apply_inverse(-1.0), 1.0);
+    }
+}
@@ -0,0 +1,348 @@
+//! Simulation of color vision deficiencies (CVD).
+//!
+//! Each deficiency affects one of the three cone types: protan the long (L) cones, deutan the
+//! medium (M) cones, and tritan the short (S) cones. A severity of 1.0 simulates dichromacy
+//! (protanopia, deuteranopia, or tritanopia), in which the affected cones are missing, and smaller
+//! severities simulate anomalous trichromacy (protanomaly, deuteranomaly, or tritanomaly).
+use nalgebra::{Matrix3, Vector3};
+
+use super::colors::{get_d65_ciexyz, Color, CIEXYY, CIEXYZ, SRGB};
+
+/// The cone type that is affected by a color vision deficiency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Deficiency {
+    Protan,
+    Deutan,
+    Tritan,
+}
+
+impl Deficiency {
+    pub const ALL: [Deficiency; 3] = [Deficiency::Protan, Deficiency::Deutan, Deficiency::Tritan];
+}
+
+/// A model of how colors appear to a person with a color vision deficiency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CvdModel {
+    /// Brettel, Viénot, and Mollon, "Computerized simulation of color appearance for
+    /// dichromats" (1997).
+    ///
+    /// Colors are projected in LMS space onto one of two half-planes through the neutral axis,
+    /// anchored at monochromatic stimuli that dichromats perceive like trichromats do.
+    Brettel1997,
+    /// Viénot, Brettel, and Mollon, "Digital video colourmaps for checking the legibility of
+    /// displays by dichromats" (1999).
+    ///
+    /// A single-plane simplification of Brettel 1997 through black, white, and the blue primary
+    /// (or the red primary for tritans). It is accurate for protans and deutans but only a rough
+    /// approximation for tritans.
+    Vienot1999,
+    /// Machado, Oliveira, and Fernandes, "A Physiologically-based Model for Simulation of Color
+    /// Vision Deficiency" (2009).
+    ///
+    /// Uses the published linear RGB matrices for severities in steps of 0.1, interpolating
+    /// between them.
+    Machado2009,
+}
+
+/// Simulate how a color appears to a person with a color vision deficiency.
+///
+/// Severity ranges from 0.0 (normal vision) to 1.0 (dichromacy). The Brettel and Viénot models only
+/// describe dichromacy, so for those models anomalous trichromacy is approximated by interpolating
+/// linearly between the original and the dichromat simulation.
+pub fn simulate_cvd<C>(color: &C, deficiency: Deficiency, severity: f64, model: CvdModel) -> C
+where
+    C: Color,
+{
+    let severity = severity.clamp(0.0, 1.0);
+    let xyz = color.to_ciexyz().to_vector3();
+    let simulated = match model {
+        CvdModel::Brettel1997 => {
+            let dichromat = brettel(xyz, deficiency);
+            xyz.lerp(&dichromat, severity)
+        }
+        CvdModel::Vienot1999 => {
+            let dichromat = vienot(xyz, deficiency);
+            xyz.lerp(&dichromat, severity)
+        }
+        CvdModel::Machado2009 => {
+            let linear = SRGB::from_color(&CIEXYZ::from_vector3(xyz)).to_linear();
+            let simulated = machado_matrix(deficiency, severity) * linear;
+            SRGB::from_linear(simulated).to_ciexyz().to_vector3()
+        }
+    };
+    C::from_ciexyz(&CIEXYZ::from_vector3(simulated))
+}
+
+/// The Hunt-Pointer-Estevez transformation from CIE XYZ to LMS cone responses.
+#[rustfmt::skip]
+fn xyz_to_lms_matrix() -> Matrix3<f64> {
+    Matrix3::new(
+         0.38971, 0.68898, -0.07868,
+        -0.22981, 1.18340,  0.04641,
+         0.0,     0.0,      1.0,
+    )
+}
+
+fn to_lms(xyz: Vector3<f64>) -> Vector3<f64> {
+    xyz_to_lms_matrix() * xyz
+}
+
+/// The inverse of `xyz_to_lms_matrix`.
+#[rustfmt::skip]
+fn lms_to_xyz_matrix() -> Matrix3<f64> {
+    Matrix3::new(
+        1.9101968340520348, -1.1121238927878747,  0.2019079567674994,
+        0.3709500882486886,  0.6290542573926132, -0.0000080551421844,
+        0.0,                 0.0,                 1.0,
+    )
+}
+
+fn from_lms(lms: Vector3<f64>) -> Vector3<f64> {
+    lms_to_xyz_matrix() * lms
+}
+
+/// The index of the cone type in an LMS vector.
+fn missing_cone(deficiency: Deficiency) -> usize {
+    match deficiency {
+        Deficiency::Protan => 0,
+        Deficiency::Deutan => 1,
+        Deficiency::Tritan => 2,
+    }
+}
+
+/// Replace the missing cone response so that the color lies on the plane through the origin with
+/// the given normal.
+fn project(lms: Vector3<f64>, normal: Vector3<f64>, missing: usize) -> Vector3<f64> {
+    let mut projected = lms;
+    let others: f64 = (0..3)
+        .filter(|i| *i != missing)
+        .map(|i| normal[i] * lms[i])
+        .sum();
+    projected[missing] = -others / normal[missing];
+    projected
+}
+
+/// The LMS response to a monochromatic stimulus with the given CIE 1931 xy chromaticity.
+fn spectral_lms(x: f64, y: f64) -> Vector3<f64> {
+    to_lms(
+        CIEXYY::from_vector3(Vector3::new(x, y, 1.0))
+            .to_ciexyz()
+            .to_vector3(),
+    )
+}
+
+fn brettel(xyz: Vector3<f64>, deficiency: Deficiency) -> Vector3<f64> {
+    let lms = to_lms(xyz);
+    let white = to_lms(get_d65_ciexyz().to_vector3());
+    // Chromaticities of the anchor wavelengths on the CIE 1931 spectral locus.
+    let (anchor1, anchor2) = match deficiency {
+        // 475 nm and 575 nm
+        Deficiency::Protan | Deficiency::Deutan => {
+            (spectral_lms(0.1096, 0.0868), spectral_lms(0.4788, 0.5202))
+        }
+        // 485 nm and 660 nm
+        Deficiency::Tritan => (spectral_lms(0.0687, 0.2007), spectral_lms(0.7300, 0.2700)),
+    };
+
+    // Choose the half-plane on the same side of the neutral axis as the color, comparing the
+    // ratio of the remaining cone responses to that of white: S/M for protans, S/L for deutans,
+    // and M/L for tritans.
+    let use_second = match deficiency {
+        Deficiency::Protan => lms[2] * white[1] < white[2] * lms[1],
+        Deficiency::Deutan => lms[2] * white[0] < white[2] * lms[0],
+        Deficiency::Tritan => lms[1] * white[0] < white[1] * lms[0],
+    };
+    let anchor = if use_second { anchor2 } else { anchor1 };
+    let normal = white.cross(&anchor);
+    from_lms(project(lms, normal, missing_cone(deficiency)))
+}
+
+fn vienot(xyz: Vector3<f64>, deficiency: Deficiency) -> Vector3<f64> {
+    let lms = to_lms(xyz);
+    let white = to_lms(get_d65_ciexyz().to_vector3());
+    let primary = match deficiency {
+        Deficiency::Protan | Deficiency::Deutan => Vector3::new(0.0, 0.0, 1.0),
+        Deficiency::Tritan => Vector3::new(1.0, 0.0, 0.0),
+    };
+    let anchor = to_lms(SRGB::from_linear(primary).to_ciexyz().to_vector3());
+    let normal = white.cross(&anchor);
+    from_lms(project(lms, normal, missing_cone(deficiency)))
+}
+
+fn machado_matrix(deficiency: Deficiency, severity: f64) -> Matrix3<f64> {
+    let table = match deficiency {
+        Deficiency::Protan => &MACHADO_PROTAN,
+        Deficiency::Deutan => &MACHADO_DEUTAN,
+        Deficiency::Tritan => &MACHADO_TRITAN,
+    };
+    let position = severity * 10.0;
+    let lower = (position.floor() as usize).min(9);
+    let fraction = position - lower as f64;
+    let matrix = |i: usize| -> Matrix3<f64> {
+        if i == 0 {
+            Matrix3::identity()
+        } else {
+            Matrix3::from_row_slice(&table[i - 1])
+        }
+    };
+    matrix(lower) * (1.0 - fraction) + matrix(lower + 1) * fraction
+}
+
+// Linear RGB matrices from Machado et al. for severities 0.1 through 1.0, in row-major order.
+
+#[rustfmt::skip]
+const MACHADO_PROTAN: [[f64; 9]; 10] = [
+    [0.856167, 0.182038, -0.038205, 0.029342, 0.955115, 0.015544, -0.002880, -0.001563, 1.004443],
+    [0.734766, 0.334872, -0.069637, 0.051840, 0.919198, 0.028963, -0.004928, -0.004209, 1.009137],
+    [0.630323, 0.465641, -0.095964, 0.069181, 0.890046, 0.040773, -0.006308, -0.007724, 1.014032],
+    [0.539009, 0.579343, -0.118352, 0.082546, 0.866121, 0.051332, -0.007136, -0.011959, 1.019095],
+    [0.458064, 0.679578, -0.137642, 0.092785, 0.846313, 0.060902, -0.007494, -0.016807, 1.024301],
+    [0.385450, 0.769005, -0.154455, 0.100526, 0.829802, 0.069673, -0.007442, -0.022190, 1.029632],
+    [0.319627, 0.849633, -0.169261, 0.106241, 0.815969, 0.077790, -0.007025, -0.028051, 1.035076],
+    [0.259411, 0.923008, -0.182420, 0.110296, 0.804340, 0.085364, -0.006276, -0.034346, 1.040622],
+    [0.203876, 0.990338, -0.194214, 0.112975, 0.794542, 0.092483, -0.005222, -0.041043, 1.046265],
+    [0.152286, 1.052583, -0.204868, 0.114503, 0.786281, 0.099216, -0.003882, -0.048116, 1.051998],
+];
+
+#[rustfmt::skip]
+const MACHADO_DEUTAN: [[f64; 9]; 10] = [
+    [0.866435, 0.177704, -0.044139, 0.049567, 0.939063, 0.011370, -0.003453, 0.007233, 0.996220],
+    [0.760729, 0.319078, -0.079807, 0.090568, 0.889315, 0.020117, -0.006027, 0.013325, 0.992702],
+    [0.675425, 0.433850, -0.109275, 0.125303, 0.847755, 0.026942, -0.007950, 0.018572, 0.989378],
+    [0.605511, 0.528560, -0.134071, 0.155318, 0.812366, 0.032316, -0.009376, 0.023176, 0.986200],
+    [0.547494, 0.607765, -0.155259, 0.181692, 0.781742, 0.036566, -0.010410, 0.027275, 0.983136],
+    [0.498864, 0.674741, -0.173604, 0.205199, 0.754872, 0.039929, -0.011131, 0.030969, 0.980162],
+    [0.457771, 0.731899, -0.189670, 0.226409, 0.731012, 0.042579, -0.011595, 0.034333, 0.977261],
+    [0.422823, 0.781057, -0.203881, 0.245752, 0.709602, 0.044646, -0.011843, 0.037423, 0.974421],
+    [0.392952, 0.823610, -0.216562, 0.263559, 0.690210, 0.046232, -0.011910, 0.040281, 0.971630],
+    [0.367322, 0.860646, -0.227968, 0.280085, 0.672501, 0.047413, -0.011820, 0.042940, 0.968881],
+];
+
+#[rustfmt::skip]
+const MACHADO_TRITAN: [[f64; 9]; 10] = [
+    [0.926670,  0.092514, -0.019184,  0.021191, 0.964503, 0.014306,  0.008437, 0.054813, 0.936750],
+    [0.895720,  0.133330, -0.029050,  0.029997, 0.945400, 0.024603,  0.013027, 0.104707, 0.882266],
+    [0.905871,  0.127791, -0.033662,  0.026856, 0.941251, 0.031893,  0.013410, 0.148296, 0.838294],
+    [0.948035,  0.089490, -0.037526,  0.014364, 0.946792, 0.038844,  0.010853, 0.193991, 0.795156],
+    [1.017277,  0.027029, -0.044306, -0.006113, 0.958479, 0.047634,  0.006379, 0.248708, 0.744913],
+    [1.104996, -0.046633, -0.058363, -0.032137, 0.971635, 0.060503,  0.001336, 0.317922, 0.680742],
+    [1.193214, -0.109812, -0.083402, -0.058496, 0.979410, 0.079086, -0.002346, 0.403492, 0.598854],
+    [1.257728, -0.139648, -0.118081, -0.078003, 0.975409, 0.102594, -0.003316, 0.501214, 0.502102],
+    [1.278864, -0.125333, -0.153531, -0.084748, 0.957674, 0.127074, -0.000989, 0.601151, 0.399838],
+    [1.255528, -0.076749, -0.178779, -0.078411, 0.930809, 0.147602,  0.004733, 0.691367, 0.303900],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difference::{delta_e, DeltaE};
+    use crate::errors::Error;
+
+    const MODELS: [CvdModel; 3] = [
+        CvdModel::Brettel1997,
+        CvdModel::Vienot1999,
+        CvdModel::Machado2009,
+    ];
+
+    #[test]
+    fn test_lms_to_xyz_matrix_is_inverse() {
+        let product = lms_to_xyz_matrix() * xyz_to_lms_matrix();
+        assert!((product - Matrix3::identity()).amax() < 1e-14);
+    }
+
+    #[test]
+    fn test_brettel_ignores_missing_cone() {
+        let white = to_lms(get_d65_ciexyz().to_vector3());
+        // Colors on both sides of the neutral axis, with the S/L and S/M ratios of some of them
+        // on opposite sides of white.
+        let colors = [
+            white.component_mul(&Vector3::new(1.2, 0.8, 1.0)),
+            white.component_mul(&Vector3::new(0.8, 1.2, 1.0)),
+            white.component_mul(&Vector3::new(0.3, 0.5, 0.4)),
+            white.component_mul(&Vector3::new(0.5, 0.3, 0.4)),
+        ];
+        for deficiency in Deficiency::ALL.iter() {
+            let missing = missing_cone(*deficiency);
+            for lms in colors.iter() {
+                let expected = brettel(from_lms(*lms), *deficiency);
+                for scale in [0.5, 2.0].iter() {
+                    let mut changed = *lms;
+                    changed[missing] *= scale;
+                    let simulated = brettel(from_lms(changed), *deficiency);
+                    assert!(
+                        (simulated - expected).amax() < 1e-12,
+                        "{:?} {:?}",
+                        deficiency,
+                        lms
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_severity_is_identity() -> Result<(), Error> {
+        let color = SRGB::from_hex("c83264")?;
+        for model in MODELS.iter() {
+            for deficiency in Deficiency::ALL.iter() {
+                let simulated = simulate_cvd(&color, *deficiency, 0.0, *model);
+                assert!((simulated.to_vector3() - color.to_vector3()).magnitude() < 1e-9);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_white_is_unchanged() {
+        let white = get_d65_ciexyz();
+        for model in MODELS.iter() {
+            for deficiency in Deficiency::ALL.iter() {
+                let simulated = simulate_cvd(&white, *deficiency, 1.0, *model);
+                assert!(
+                    (simulated.to_vector3() - white.to_vector3()).magnitude() < 1e-3,
+                    "{:?} {:?}: {:?}",
+                    model,
+                    deficiency,
+                    simulated
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_machado_severity_one_matrix() -> Result<(), Error> {
+        let red = SRGB::from_hex("f00")?;
+        let simulated = simulate_cvd(&red, Deficiency::Protan, 1.0, CvdModel::Machado2009);
+        let expected = SRGB::from_linear(Vector3::new(0.152286, 0.114503, -0.003882));
+        assert!((simulated.to_vector3() - expected.to_vector3()).magnitude() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_red_green_confusion() -> Result<(), Error> {
+        let red = SRGB::from_hex("d03030")?;
+        let green = SRGB::from_hex("30a030")?;
+        let normal = delta_e(red, green, DeltaE::CIEDE2000);
+        for model in MODELS.iter() {
+            for deficiency in &[Deficiency::Protan, Deficiency::Deutan] {
+                let simulate = |c: &SRGB| simulate_cvd(c, *deficiency, 1.0, *model);
+                let simulated = delta_e(simulate(&red), simulate(&green), DeltaE::CIEDE2000);
+                assert!(simulated < normal / 2.0, "{:?} {:?}", model, deficiency);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_dichromat_simulation_is_idempotent() -> Result<(), Error> {
+        let color = SRGB::from_hex("3070c0")?;
+        for model in &[CvdModel::Brettel1997, CvdModel::Vienot1999] {
+            for deficiency in Deficiency::ALL.iter() {
+                let once = simulate_cvd(&color, *deficiency, 1.0, *model);
+                let twice = simulate_cvd(&once, *deficiency, 1.0, *model);
+                assert!((once.to_vector3() - twice.to_vector3()).magnitude() < 1e-9);
+            }
+        }
+        Ok(())
+    }
+}
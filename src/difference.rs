@@ -0,0 +1,200 @@
+//! Color difference (ΔE) metrics.
+//!
+//! All metrics are evaluated in CIELAB and reported on the conventional scale, where a difference
+//! of roughly 1.0 is a just-noticeable difference. Note that this is 100 times larger than the
+//! normalized CIELAB components used by `colors::CIELAB`.
+use std::f64::consts::PI;
+
+use super::colors::{Color, CIELAB};
+
+/// A formula for computing the perceptual distance between two colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeltaE {
+    /// Euclidean distance in CIELAB.
+    ///
+    /// See https://en.wikipedia.org/wiki/Color_difference#CIE76
+    CIE76,
+    /// CIE 1994 color difference, using the graphic arts weighting factors.
+    ///
+    /// See https://en.wikipedia.org/wiki/Color_difference#CIE94
+    CIE94,
+    /// CIE 2000 color difference.
+    ///
+    /// See https://en.wikipedia.org/wiki/Color_difference#CIEDE2000
+    CIEDE2000,
+}
+
+impl DeltaE {
+    /// Compute the difference between two colors that are already in CIELAB.
+    pub fn compute(self, lab1: &CIELAB, lab2: &CIELAB) -> f64 {
+        let (l1, a1, b1) = scaled_lab(lab1);
+        let (l2, a2, b2) = scaled_lab(lab2);
+        match self {
+            DeltaE::CIE76 => cie76(l1, a1, b1, l2, a2, b2),
+            DeltaE::CIE94 => cie94(l1, a1, b1, l2, a2, b2),
+            DeltaE::CIEDE2000 => ciede2000(l1, a1, b1, l2, a2, b2),
+        }
+    }
+}
+
+/// Compute the difference between two colors using the given metric.
+pub fn delta_e<C>(c1: C, c2: C, metric: DeltaE) -> f64
+where
+    C: Color,
+{
+    let lab1: CIELAB = c1.to_color();
+    let lab2: CIELAB = c2.to_color();
+    metric.compute(&lab1, &lab2)
+}
+
+fn scaled_lab(lab: &CIELAB) -> (f64, f64, f64) {
+    let v = lab.to_vector3() * 100.0;
+    (v[0], v[1], v[2])
+}
+
+fn cie76(l1: f64, a1: f64, b1: f64, l2: f64, a2: f64, b2: f64) -> f64 {
+    ((l1 - l2).powf(2.0) + (a1 - a2).powf(2.0) + (b1 - b2).powf(2.0)).sqrt()
+}
+
+fn cie94(l1: f64, a1: f64, b1: f64, l2: f64, a2: f64, b2: f64) -> f64 {
+    let (k_l, k_1, k_2) = (1.0, 0.045, 0.015);
+
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+    let c2 = (a2 * a2 + b2 * b2).sqrt();
+    let delta_l = l1 - l2;
+    let delta_c = c1 - c2;
+    let delta_a = a1 - a2;
+    let delta_b = b1 - b2;
+    // Rounding can make this slightly negative when the hues are equal.
+    let delta_h_squared = (delta_a * delta_a + delta_b * delta_b - delta_c * delta_c).max(0.0);
+
+    let s_l = 1.0;
+    let s_c = 1.0 + k_1 * c1;
+    let s_h = 1.0 + k_2 * c1;
+
+    ((delta_l / (k_l * s_l)).powf(2.0) + (delta_c / s_c).powf(2.0) + delta_h_squared / (s_h * s_h))
+        .sqrt()
+}
+
+#[allow(clippy::many_single_char_names)]
+fn ciede2000(l1: f64, a1: f64, b1: f64, l2: f64, a2: f64, b2: f64) -> f64 {
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+    let c2 = (a2 * a2 + b2 * b2).sqrt();
+    let c_bar = (c1 + c2) / 2.0;
+    let c_bar_7 = c_bar.powf(7.0);
+    let g = 0.5 * (1.0 - (c_bar_7 / (c_bar_7 + 25.0_f64.powf(7.0))).sqrt());
+
+    let a1_prime = (1.0 + g) * a1;
+    let a2_prime = (1.0 + g) * a2;
+    let c1_prime = (a1_prime * a1_prime + b1 * b1).sqrt();
+    let c2_prime = (a2_prime * a2_prime + b2 * b2).sqrt();
+    let h1_prime = hue_degrees(a1_prime, b1);
+    let h2_prime = hue_degrees(a2_prime, b2);
+
+    let delta_l_prime = l2 - l1;
+    let delta_c_prime = c2_prime - c1_prime;
+    let delta_h_prime = if c1_prime * c2_prime == 0.0 {
+        0.0
+    } else if (h2_prime - h1_prime).abs() <= 180.0 {
+        h2_prime - h1_prime
+    } else if h2_prime - h1_prime > 180.0 {
+        h2_prime - h1_prime - 360.0
+    } else {
+        h2_prime - h1_prime + 360.0
+    };
+    let delta_big_h_prime =
+        2.0 * (c1_prime * c2_prime).sqrt() * (delta_h_prime.to_radians() / 2.0).sin();
+
+    let l_bar_prime = (l1 + l2) / 2.0;
+    let c_bar_prime = (c1_prime + c2_prime) / 2.0;
+    let h_bar_prime = if c1_prime * c2_prime == 0.0 {
+        h1_prime + h2_prime
+    } else if (h1_prime - h2_prime).abs() <= 180.0 {
+        (h1_prime + h2_prime) / 2.0
+    } else if h1_prime + h2_prime < 360.0 {
+        (h1_prime + h2_prime + 360.0) / 2.0
+    } else {
+        (h1_prime + h2_prime - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar_prime - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar_prime).to_radians().cos()
+        + 0.32 * (3.0 * h_bar_prime + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar_prime - 63.0).to_radians().cos();
+
+    let delta_theta = 30.0 * (-((h_bar_prime - 275.0) / 25.0).powf(2.0)).exp();
+    let c_bar_prime_7 = c_bar_prime.powf(7.0);
+    let r_c = 2.0 * (c_bar_prime_7 / (c_bar_prime_7 + 25.0_f64.powf(7.0))).sqrt();
+    let s_l = 1.0
+        + (0.015 * (l_bar_prime - 50.0).powf(2.0)) / (20.0 + (l_bar_prime - 50.0).powf(2.0)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar_prime;
+    let s_h = 1.0 + 0.015 * c_bar_prime * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let l_term = delta_l_prime / s_l;
+    let c_term = delta_c_prime / s_c;
+    let h_term = delta_big_h_prime / s_h;
+
+    (l_term * l_term + c_term * c_term + h_term * h_term + r_t * c_term * h_term).sqrt()
+}
+
+fn hue_degrees(a: f64, b: f64) -> f64 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        let h = b.atan2(a) * 180.0 / PI;
+        if h < 0.0 {
+            h + 360.0
+        } else {
+            h
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    fn lab(l: f64, a: f64, b: f64) -> CIELAB {
+        CIELAB::from_vector3(Vector3::new(l, a, b) / 100.0)
+    }
+
+    #[test]
+    fn test_identical_colors() {
+        let color = lab(50.0, 20.0, -10.0);
+        for metric in &[DeltaE::CIE76, DeltaE::CIE94, DeltaE::CIEDE2000] {
+            assert_eq!(metric.compute(&color, &color), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_cie76() {
+        let difference = DeltaE::CIE76.compute(&lab(50.0, 0.0, 0.0), &lab(53.0, 4.0, 0.0));
+        assert!((difference - 5.0).abs() < 1e-9);
+    }
+
+    // Reference values from Sharma, Wu, and Dalal, "The CIEDE2000 Color-Difference Formula:
+    // Implementation Notes, Supplementary Test Data, and Mathematical Observations" (2005).
+    #[test]
+    fn test_ciede2000_reference_data() {
+        let pairs = [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (lab(50.0, 0.0, 0.0), lab(50.0, -1.0, 2.0), 2.3669),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+        ];
+        for (lab1, lab2, expected) in pairs.iter() {
+            let difference = DeltaE::CIEDE2000.compute(lab1, lab2);
+            assert!(
+                (difference - expected).abs() < 1e-4,
+                "Expected {} to be {}",
+                difference,
+                expected
+            );
+        }
+    }
+}
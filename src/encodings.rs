@@ -5,7 +5,7 @@ use nalgebra::Vector3;
 
 use super::errors::Error;
 use super::errors::Error::HexadecimalParseError;
-use super::nalgebra_helpers::Vector3OptionExt;
+use super::nalgebra_helpers::Array3OptionExt;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 struct Real(f64);
@@ -50,20 +50,20 @@ fn real_to_byte(real: Real) -> Byte {
 
 pub fn parse_hex_string(hex_string: &str) -> Result<Vector3<f64>, Error> {
     let chars: Vec<char> = hex_string.chars().collect();
-    let vec_hex_bytes = if chars.len() == 6 {
-        Vector3::new(
+    let hex_bytes = if chars.len() == 6 {
+        [
             HexByte(chars[0], chars[1]),
             HexByte(chars[2], chars[3]),
             HexByte(chars[4], chars[5]),
-        )
+        ]
     } else if chars.len() == 3 {
         // Three-digit shorthand notation
         // See https://www.w3.org/TR/css-color-3/#rgb-color
-        Vector3::new(
+        [
             HexByte(chars[0], chars[0]),
             HexByte(chars[1], chars[1]),
             HexByte(chars[2], chars[2]),
-        )
+        ]
     } else {
         return Err(HexadecimalParseError(format!(
             "A web hexadecimal color must be either 6 or 3 digits long, not {}",
@@ -71,21 +71,257 @@ pub fn parse_hex_string(hex_string: &str) -> Result<Vector3<f64>, Error> {
         )));
     };
 
-    let vec_opt_bytes = vec_hex_bytes.map(|x| x.to_byte());
-    let vec_bytes = vec_opt_bytes.sequence_option().ok_or_else(|| {
+    let opt_bytes = hex_bytes.map(|x| x.to_byte());
+    let vec_bytes = opt_bytes.sequence_option().ok_or_else(|| {
         HexadecimalParseError("A hexadecimal color may only contain hexadecimal digits".to_string())
     })?;
     Ok(vec_bytes.map(|b| b.to_real().0))
 }
 
-pub fn to_hex_string(vec: Vector3<f64>) -> String {
+/// Quantize each component to a byte, where 0.0 maps to 0 and 1.0 maps to 255.
+pub fn to_bytes(vec: Vector3<f64>) -> [u8; 3] {
     let byte_vec = vec.map(Real).map(real_to_byte).map(|x| x.0);
-    format!(
-        "{:0>2x}{:0>2x}{:0>2x}",
-        byte_vec[0], byte_vec[1], byte_vec[2]
+    [byte_vec[0], byte_vec[1], byte_vec[2]]
+}
+
+/// Interpret bytes as components, where 0 maps to 0.0 and 255 maps to 1.0.
+pub fn from_bytes(bytes: [u8; 3]) -> Vector3<f64> {
+    Vector3::new(
+        Byte(bytes[0]).to_real().0,
+        Byte(bytes[1]).to_real().0,
+        Byte(bytes[2]).to_real().0,
+    )
+}
+
+pub fn to_hex_string(vec: Vector3<f64>) -> String {
+    let bytes = to_bytes(vec);
+    format!("{:0>2x}{:0>2x}{:0>2x}", bytes[0], bytes[1], bytes[2])
+}
+
+/// The largest value of an integer component with a number of bits.
+///
+/// Panics unless there are between 1 and 16 bits.
+fn max_integer(bits: u32) -> f64 {
+    assert!(
+        (1..=16).contains(&bits),
+        "Integer components have between 1 and 16 bits, not {}",
+        bits
+    );
+    f64::from((1_u32 << bits) - 1)
+}
+
+fn real_to_integer(real: f64, max: f64) -> u16 {
+    (real * max).round().clamp(0.0, max) as u16
+}
+
+/// Quantize each component to an integer with a number of bits, such as 10, 12 or 16, where 0.0
+/// maps to 0 and 1.0 maps to the largest value.
+///
+/// Panics unless there are between 1 and 16 bits.
+pub fn to_integers(vec: Vector3<f64>, bits: u32) -> [u16; 3] {
+    let max = max_integer(bits);
+    [
+        real_to_integer(vec[0], max),
+        real_to_integer(vec[1], max),
+        real_to_integer(vec[2], max),
+    ]
+}
+
+/// Interpret integers with a number of bits as components, where the largest value maps to 1.0.
+/// Bits above the number of bits are ignored.
+///
+/// Panics unless there are between 1 and 16 bits.
+pub fn from_integers(integers: [u16; 3], bits: u32) -> Vector3<f64> {
+    let max = max_integer(bits);
+    let mask = max as u16;
+    Vector3::from(integers.map(|x| f64::from(x & mask) / max))
+}
+
+/// Encode a number as the bits of an IEEE 754 half precision float, rounding to the nearest
+/// representable value, with ties to even.
+pub fn to_f16(value: f64) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 48) & 0x8000) as u16;
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let mantissa = bits & 0x000f_ffff_ffff_ffff;
+    if exponent == 0x7ff {
+        // Infinity, or a quiet NaN.
+        return sign | 0x7c00 | if mantissa != 0 { 0x0200 } else { 0 };
+    }
+    let exponent = exponent - 1023 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    let (full, shift) = if exponent > 0 {
+        ((exponent as u64) << 52 | mantissa, 42)
+    } else if exponent >= -10 {
+        // A subnormal half float, whose implicit leading bit is explicit.
+        (mantissa | 1 << 52, (43 - exponent) as u32)
+    } else {
+        return sign;
+    };
+    let half = full >> shift;
+    let remainder = full & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let round_up = remainder > halfway || (remainder == halfway && half & 1 == 1);
+    // Rounding up may carry into the exponent, which correctly reaches the next power of two or
+    // infinity.
+    sign | (half + u64::from(round_up)) as u16
+}
+
+/// Decode the bits of an IEEE 754 half precision float.
+pub fn from_f16(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((half >> 10) & 0x1f);
+    let mantissa = f64::from(half & 0x03ff);
+    sign * match exponent {
+        0 => mantissa * 2_f64.powi(-24),
+        0x1f if mantissa == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2_f64.powi(exponent - 15),
+    }
+}
+
+/// Pack components into 16 bits, with 5 bits of red in the high bits, 6 of green and 5 of blue.
+pub fn to_rgb565(vec: Vector3<f64>) -> u16 {
+    let r = real_to_integer(vec[0], 31.0);
+    let g = real_to_integer(vec[1], 63.0);
+    let b = real_to_integer(vec[2], 31.0);
+    r << 11 | g << 5 | b
+}
+
+/// Unpack components from 16 bits, with 5 bits of red in the high bits, 6 of green and 5 of
+/// blue.
+pub fn from_rgb565(packed: u16) -> Vector3<f64> {
+    Vector3::new(
+        f64::from(packed >> 11) / 31.0,
+        f64::from((packed >> 5) & 0x3f) / 63.0,
+        f64::from(packed & 0x1f) / 31.0,
+    )
+}
+
+/// Pack components and alpha into 32 bits, with 10 bits of red in the low bits, then 10 of green,
+/// 10 of blue and 2 of alpha, as in the common RGB10_A2 and R10G10B10A2 formats.
+pub fn to_rgba1010102(vec: Vector3<f64>, alpha: f64) -> u32 {
+    let [r, g, b] = to_integers(vec, 10).map(u32::from);
+    let a = u32::from(real_to_integer(alpha, 3.0));
+    a << 30 | b << 20 | g << 10 | r
+}
+
+/// Unpack components and alpha from 32 bits, with 10 bits of red in the low bits, then 10 of
+/// green, 10 of blue and 2 of alpha.
+pub fn from_rgba1010102(packed: u32) -> (Vector3<f64>, f64) {
+    let component = |shift: u32| f64::from((packed >> shift) & 0x3ff) / 1023.0;
+    let rgb = Vector3::new(component(0), component(10), component(20));
+    (rgb, f64::from(packed >> 30) / 3.0)
+}
+
+/// Encode components, which may exceed 1.0, in the RGBE format of Radiance HDR images: a mantissa
+/// byte per component and an exponent byte shared by all of them. Negative components are
+/// encoded as 0, and components too large for the format, including infinity, saturate.
+///
+/// See Greg Ward, "Real Pixels", Graphics Gems II (1991).
+pub fn to_rgbe(vec: Vector3<f64>) -> [u8; 4] {
+    let vec = vec.map(|x| x.max(0.0));
+    let max = vec.max();
+    if max < 1e-32 || max.is_nan() {
+        return [0; 4];
+    }
+    if max.is_infinite() {
+        return [255, 255, 255, 255];
+    }
+    // The exponent e such that max = m * 2^e, with m in [0.5, 1).
+    let mut exponent = max.log2().floor() as i32 + 1;
+    if max / 2_f64.powi(exponent) >= 1.0 {
+        exponent += 1;
+    } else if max / 2_f64.powi(exponent) < 0.5 {
+        exponent -= 1;
+    }
+    if exponent > 127 {
+        return [255, 255, 255, 255];
+    }
+    let scale = 256.0 / 2_f64.powi(exponent);
+    let mantissa = |x: f64| (x * scale).floor().min(255.0) as u8;
+    [
+        mantissa(vec[0]),
+        mantissa(vec[1]),
+        mantissa(vec[2]),
+        (exponent + 128) as u8,
+    ]
+}
+
+/// Decode components from the RGBE format of Radiance HDR images, taking the center of the range
+/// of values that each mantissa encodes, as Radiance does.
+pub fn from_rgbe(rgbe: [u8; 4]) -> Vector3<f64> {
+    if rgbe[3] == 0 {
+        return Vector3::zeros();
+    }
+    let scale = 2_f64.powi(i32::from(rgbe[3]) - (128 + 8));
+    Vector3::new(
+        (f64::from(rgbe[0]) + 0.5) * scale,
+        (f64::from(rgbe[1]) + 0.5) * scale,
+        (f64::from(rgbe[2]) + 0.5) * scale,
     )
 }
 
+/// A component of an interleaved pixel buffer, which encodes a real number.
+///
+/// Integer components map [0, 1] to their full range, rounding to the nearest value and clamping
+/// anything outside it. Float components are stored as is, so they may exceed [0, 1].
+pub trait PixelComponent: Copy {
+    fn to_real(self) -> f64;
+
+    fn from_real(real: f64) -> Self;
+}
+
+impl PixelComponent for u8 {
+    fn to_real(self) -> f64 {
+        Byte(self).to_real().0
+    }
+
+    fn from_real(real: f64) -> Self {
+        real_to_byte(Real(real)).0
+    }
+}
+
+impl PixelComponent for u16 {
+    fn to_real(self) -> f64 {
+        f64::from(self) / 65535.0
+    }
+
+    fn from_real(real: f64) -> Self {
+        (real * 65535.0).round().clamp(0.0, 65535.0) as u16
+    }
+}
+
+impl PixelComponent for f32 {
+    fn to_real(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_real(real: f64) -> Self {
+        real as f32
+    }
+}
+
+/// The order of the components of each pixel in an interleaved pixel buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelLayout {
+    Rgb,
+    /// RGB followed by straight, not premultiplied, alpha, which conversions leave unchanged.
+    Rgba,
+}
+
+impl PixelLayout {
+    /// The number of components of each pixel.
+    pub fn channels(self) -> usize {
+        match self {
+            PixelLayout::Rgb => 3,
+            PixelLayout::Rgba => 4,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,11 +389,124 @@ mod tests {
 
         for _ in 0..100 {
             let string = &format!("{:0>6x}", rng.gen::<u64>())[0..6];
-            assert_eq!(
-                to_hex_string(parse_hex_string(&string)?),
-                string.to_string()
-            );
+            assert_eq!(to_hex_string(parse_hex_string(string)?), string.to_string());
         }
         Ok(())
     }
+
+    #[test]
+    fn test_pixel_components() {
+        assert_eq!(u8::from_real(0.5), 128);
+        assert_eq!(u8::from_real(1.2), 255);
+        assert_eq!(u16::from_real(-0.1), 0);
+        assert_eq!(u16::from_real(0.5), 32768);
+        assert_eq!(u16::from_real(1.0), 65535);
+        assert_eq!(65535_u16.to_real(), 1.0);
+        assert_eq!(f32::from_real(1.5), 1.5);
+    }
+
+    #[test]
+    fn test_integers() {
+        let vec = Vector3::new(0.0, 0.5, 1.0);
+        assert_eq!(to_integers(vec, 10), [0, 512, 1023]);
+        assert_eq!(to_integers(vec, 12), [0, 2048, 4095]);
+        assert_eq!(to_integers(vec, 16), [0, 32768, 65535]);
+        assert_eq!(
+            to_integers(Vector3::new(-0.1, 1.1, 0.25), 10),
+            [0, 1023, 256]
+        );
+        assert_eq!(from_integers([0, 1023, 0xfc00 | 512], 10)[1], 1.0);
+        assert_eq!(
+            from_integers([0, 1023, 0xfc00 | 512], 10)[2],
+            512.0 / 1023.0
+        );
+        for bits in [10, 12, 16].iter() {
+            for i in 0..=100 {
+                let vec = Vector3::repeat(f64::from(i) / 100.0);
+                let round_trip = from_integers(to_integers(vec, *bits), *bits);
+                assert!((round_trip - vec).amax() <= 0.5 / max_integer(*bits));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_integers_too_many_bits() {
+        to_integers(Vector3::zeros(), 17);
+    }
+
+    #[test]
+    fn test_f16() {
+        assert_eq!(to_f16(1.0), 0x3c00);
+        assert_eq!(to_f16(-2.0), 0xc000);
+        assert_eq!(to_f16(0.5), 0x3800);
+        assert_eq!(to_f16(1.0 / 3.0), 0x3555);
+        assert_eq!(to_f16(65504.0), 0x7bff);
+        assert_eq!(to_f16(65520.0), 0x7c00);
+        assert_eq!(to_f16(f64::NEG_INFINITY), 0xfc00);
+        assert_eq!(to_f16(-0.0), 0x8000);
+        assert_eq!(to_f16(2_f64.powi(-24)), 0x0001);
+        assert_eq!(to_f16(2_f64.powi(-25)), 0x0000);
+        assert_eq!(to_f16(1.5 * 2_f64.powi(-25)), 0x0001);
+        assert_eq!(to_f16(1023.0 * 2_f64.powi(-24) + 2_f64.powi(-25)), 0x0400);
+        // Ties round to even.
+        assert_eq!(to_f16(1.0 + 2_f64.powi(-11)), 0x3c00);
+        assert_eq!(to_f16(1.0 + 3.0 * 2_f64.powi(-11)), 0x3c02);
+        assert!(from_f16(to_f16(f64::NAN)).is_nan());
+
+        assert_eq!(from_f16(0x3c00), 1.0);
+        assert_eq!(from_f16(0x7bff), 65504.0);
+        assert_eq!(from_f16(0x0001), 2_f64.powi(-24));
+        assert_eq!(from_f16(0xfc00), f64::NEG_INFINITY);
+        for half in 0..0x7c00 {
+            assert_eq!(to_f16(from_f16(half)), half);
+        }
+    }
+
+    #[test]
+    fn test_rgb565() {
+        assert_eq!(to_rgb565(Vector3::new(1.0, 0.0, 0.0)), 0xf800);
+        assert_eq!(to_rgb565(Vector3::new(0.0, 1.0, 0.0)), 0x07e0);
+        assert_eq!(to_rgb565(Vector3::new(1.0, 1.0, 1.0)), 0xffff);
+        assert_eq!(from_rgb565(0x07e0), Vector3::new(0.0, 1.0, 0.0));
+        for packed in [0x1234, 0xabcd, 0x0001].iter() {
+            assert_eq!(to_rgb565(from_rgb565(*packed)), *packed);
+        }
+    }
+
+    #[test]
+    fn test_rgba1010102() {
+        let packed = to_rgba1010102(Vector3::new(1.0, 0.0, 0.5), 1.0);
+        assert_eq!(packed, 0b11 << 30 | 512 << 20 | 1023);
+        let (rgb, alpha) = from_rgba1010102(packed);
+        assert_eq!(rgb[0], 1.0);
+        assert_eq!(rgb[2], 512.0 / 1023.0);
+        assert_eq!(alpha, 1.0);
+        assert_eq!(from_rgba1010102(1 << 30).1, 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_rgbe() {
+        assert_eq!(to_rgbe(Vector3::new(1.0, 1.0, 1.0)), [128, 128, 128, 129]);
+        assert_eq!(to_rgbe(Vector3::new(0.5, 0.25, 0.0)), [128, 64, 0, 128]);
+        assert_eq!(to_rgbe(Vector3::zeros()), [0; 4]);
+        assert_eq!(to_rgbe(Vector3::new(-1.0, 0.0, 0.0)), [0; 4]);
+        assert_eq!(to_rgbe(Vector3::new(f64::INFINITY, 0.0, 0.0)), [255; 4]);
+        assert_eq!(to_rgbe(Vector3::new(1e300, 0.0, 0.0)), [255; 4]);
+        assert_eq!(from_rgbe([0, 0, 0, 0]), Vector3::zeros());
+        assert_eq!(
+            from_rgbe([128, 0, 0, 129]),
+            Vector3::new(1.00390625, 0.00390625, 0.00390625)
+        );
+        for vec in [
+            Vector3::new(1.0, 0.5, 0.25),
+            Vector3::new(1000.0, 20.0, 3.0),
+            Vector3::new(0.001, 0.002, 0.003),
+        ]
+        .iter()
+        {
+            let round_trip = from_rgbe(to_rgbe(*vec));
+            assert!((round_trip - vec).amax() <= vec.max() / 128.0);
+        }
+    }
 }
@@ -1,4 +1,9 @@
 #[derive(Debug, PartialEq)]
 pub enum Error {
     HexadecimalParseError(String),
+    OscParseError(String),
+    SpectralDataError(String),
+    ColorTemperatureError(String),
+    IccParseError(String),
+    PixelBufferError(String),
 }
@@ -0,0 +1,85 @@
+//! Helpers for keeping colors within the range that a display can reproduce.
+use super::colors::{Color, DisplayP3, LchSpace, SRGB};
+
+/// How far outside of [0, 1] a device component may be before it is considered out of gamut.
+///
+/// The fixed-point profile matrices do not map white exactly to 1.0, so a small tolerance is
+/// needed.
+const TOLERANCE: f64 = 1e-4;
+
+/// The set of colors reproducible by an RGB color space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gamut {
+    SRGB,
+    DisplayP3,
+}
+
+impl Gamut {
+    /// Whether a color can be represented in this gamut without clipping.
+    pub fn contains<C>(self, color: &C) -> bool
+    where
+        C: Color,
+    {
+        let device = match self {
+            Gamut::SRGB => color.to_color::<SRGB>().to_vector3(),
+            Gamut::DisplayP3 => color.to_color::<DisplayP3>().to_vector3(),
+        };
+        device
+            .iter()
+            .all(|x| *x >= -TOLERANCE && *x <= 1.0 + TOLERANCE)
+    }
+
+    /// Find the largest chroma of the given lightness and hue that is within this gamut.
+    ///
+    /// Returns 0.0 if even the neutral color of that lightness is out of gamut, and for black and
+    /// white, which have no chroma.
+    pub fn max_chroma(self, space: LchSpace, l: f64, h: f64) -> f64 {
+        if l <= 0.0 || l >= 1.0 {
+            return 0.0;
+        }
+        let contains = |c: f64| self.contains(&space.from_lch::<SRGB>(l, c, h));
+        let mut low = 0.0;
+        let mut high = match space {
+            LchSpace::CIELUV => 2.0,
+            LchSpace::OKLCH => 0.5,
+        };
+        if !contains(low) {
+            return 0.0;
+        }
+        if contains(high) {
+            return high;
+        }
+        for _ in 0..40 {
+            let mid = (low + high) / 2.0;
+            if contains(mid) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_chroma_black_and_white() {
+        for space in &[LchSpace::CIELUV, LchSpace::OKLCH] {
+            assert_eq!(Gamut::SRGB.max_chroma(*space, 0.0, 1.0), 0.0);
+            assert_eq!(Gamut::SRGB.max_chroma(*space, 1.0, 1.0), 0.0);
+            assert!(Gamut::SRGB.max_chroma(*space, 0.5, 1.0) > 0.05);
+        }
+    }
+
+    #[test]
+    fn test_display_p3_is_wider_than_srgb() {
+        for space in &[LchSpace::CIELUV, LchSpace::OKLCH] {
+            let srgb = Gamut::SRGB.max_chroma(*space, 0.6, 2.5);
+            let display_p3 = Gamut::DisplayP3.max_chroma(*space, 0.6, 2.5);
+            assert!(display_p3 > srgb);
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use std::sync::Arc;
+
+use super::LutTransform;
+use crate::colors::{Color, CIEXYZ};
+
+/// A color in the CMYK space of a printer profile.
+///
+/// Unlike the other color types, the meaning of CMYK components depends on the printer, ink and
+/// paper, so each color carries the profile that defines it, and conversions go through its
+/// profile connection space.
+#[derive(Clone, Debug)]
+pub struct CMYK {
+    components: [f64; 4],
+    profile: Arc<LutTransform>,
+}
+
+impl CMYK {
+    /// Create a color from cyan, magenta, yellow and black components in [0, 1].
+    ///
+    /// Returns `None` if the profile does not have four device channels.
+    pub fn new(components: [f64; 4], profile: Arc<LutTransform>) -> Option<Self> {
+        if profile.to_pcs.input_channels != 4 {
+            return None;
+        }
+        Some(CMYK {
+            components,
+            profile,
+        })
+    }
+
+    /// Convert a color to CMYK with the BToA LUT of a profile.
+    ///
+    /// Returns `None` if the profile does not have four device channels or a BToA LUT.
+    pub fn from_color<C>(color: &C, profile: Arc<LutTransform>) -> Option<Self>
+    where
+        C: Color,
+    {
+        let device = profile.from_color(color)?;
+        if device.len() != 4 {
+            return None;
+        }
+        Self::new([device[0], device[1], device[2], device[3]], profile)
+    }
+
+    /// The cyan, magenta, yellow and black components.
+    pub fn components(&self) -> [f64; 4] {
+        self.components
+    }
+
+    /// The profile that defines the components.
+    pub fn profile(&self) -> &Arc<LutTransform> {
+        &self.profile
+    }
+
+    pub fn to_ciexyz(&self) -> CIEXYZ {
+        self.profile.to_color(&self.components)
+    }
+
+    pub fn to_color<C>(&self) -> C
+    where
+        C: Color,
+    {
+        self.profile.to_color(&self.components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Matrix3, Vector3};
+
+    use super::*;
+    use crate::colors::{RGBColorSpace, CIELAB, SRGB};
+    use crate::errors::Error;
+    use crate::icc::{
+        DisplayProfile, IccProfile, IccTransform, RenderingIntent, Signature, CMYK_SPACE,
+        LAB_SPACE, XYZ_SPACE,
+    };
+
+    /// The colorants of a made-up printer with linear sRGB primaries, adapted to D50.
+    #[rustfmt::skip]
+    fn colorants() -> Matrix3<f64> {
+        Matrix3::new(
+            28578.0, 25241.0,  9376.0,
+            14581.0, 46981.0,  3972.0,
+              912.0,  6362.0, 46799.0,
+        ) / 65536.0
+    }
+
+    fn build_profile(space: &Signature, pcs: &Signature, tags: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut offset = 128 + 4 + 12 * tags.len();
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut body = Vec::new();
+        for (signature, data) in tags {
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&(offset as u32).to_be_bytes());
+            table.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            body.extend_from_slice(data);
+            body.resize(body.len().div_ceil(4) * 4, 0);
+            offset = 128 + 4 + 12 * tags.len() + body.len();
+        }
+        let mut header = vec![0; 128];
+        header[0..4].copy_from_slice(&(offset as u32).to_be_bytes());
+        header[8] = 4;
+        header[12..16].copy_from_slice(b"prtr");
+        header[16..20].copy_from_slice(space);
+        header[20..24].copy_from_slice(pcs);
+        header[36..40].copy_from_slice(b"acsp");
+        for (i, v) in [63190_i32, 65536, 54061].iter().enumerate() {
+            header[68 + 4 * i..72 + 4 * i].copy_from_slice(&v.to_be_bytes());
+        }
+        [header, table, body].concat()
+    }
+
+    fn u16_values(values: &[f64]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|v| ((v * 65535.0).round() as u16).to_be_bytes().to_vec())
+            .collect()
+    }
+
+    fn identity_table(channels: usize) -> Vec<u8> {
+        (0..channels)
+            .flat_map(|_| u16_values(&[0.0, 1.0]))
+            .collect()
+    }
+
+    /// A lut16Type from CMYK to PCS XYZ, where the linear RGB of the printer is
+    /// ((1 - c)(1 - k), (1 - m)(1 - k), (1 - y)(1 - k)).
+    fn a_to_b() -> Vec<u8> {
+        let mut data = b"mft2\0\0\0\0\x04\x03\x02\0".to_vec();
+        for value in [1, 0, 0, 0, 1, 0, 0, 0, 1].iter() {
+            data.extend_from_slice(&(value * 65536_i32).to_be_bytes());
+        }
+        data.extend_from_slice(&[0, 2, 0, 2]);
+        data.extend(identity_table(4));
+        for i in 0..16 {
+            let [c, m, y, k] = [i >> 3 & 1, i >> 2 & 1, i >> 1 & 1, i & 1].map(f64::from);
+            let rgb = Vector3::new(1.0 - c, 1.0 - m, 1.0 - y) * (1.0 - k);
+            let xyz = colorants() * rgb * (32768.0 / 65535.0);
+            data.extend(u16_values(xyz.as_slice()));
+        }
+        data.extend(identity_table(3));
+        data
+    }
+
+    /// A lutBtoAType from PCS XYZ to CMYK without black, using a matrix to reach linear RGB.
+    fn b_to_a() -> Vec<u8> {
+        let para = |data: &mut Vec<u8>| {
+            data.extend_from_slice(b"para\0\0\0\0\0\0\0\0");
+            data.extend_from_slice(&65536_i32.to_be_bytes());
+        };
+        let mut data = b"mBA \0\0\0\0\x03\x04\0\0".to_vec();
+        for offset in [32_u32, 80, 128, 176, 260].iter() {
+            data.extend_from_slice(&offset.to_be_bytes());
+        }
+        // B curves at 32
+        for _ in 0..3 {
+            para(&mut data);
+        }
+        // Matrix at 80
+        let matrix = colorants()
+            .try_inverse()
+            .expect("The colorants are independent.")
+            * (65535.0 / 32768.0);
+        for value in matrix.transpose().iter().chain([0.0; 3].iter()) {
+            data.extend_from_slice(&((value * 65536.0).round() as i32).to_be_bytes());
+        }
+        data.resize(128, 0);
+        // M curves at 128
+        for _ in 0..3 {
+            para(&mut data);
+        }
+        data.resize(176, 0);
+        // CLUT at 176
+        data.extend_from_slice(&[2, 2, 2]);
+        data.resize(192, 0);
+        data.extend_from_slice(&[2, 0, 0, 0]);
+        for i in 0..8 {
+            let [r, g, b] = [i >> 2 & 1, i >> 1 & 1, i & 1].map(f64::from);
+            data.extend(u16_values(&[1.0 - r, 1.0 - g, 1.0 - b, 0.0]));
+        }
+        // A curves at 260
+        for _ in 0..4 {
+            para(&mut data);
+        }
+        data
+    }
+
+    fn cmyk_profile() -> Result<Arc<LutTransform>, Error> {
+        let data = build_profile(
+            &CMYK_SPACE,
+            &XYZ_SPACE,
+            &[(b"A2B0", a_to_b()), (b"B2A0", b_to_a())],
+        );
+        Ok(Arc::new(
+            IccProfile::parse(&data)?.lut_transform(RenderingIntent::Perceptual)?,
+        ))
+    }
+
+    fn assert_srgb(cmyk: &CMYK, expected: [f64; 3]) {
+        let srgb: SRGB = cmyk.to_color();
+        let difference = srgb.to_vector3() - Vector3::from(expected);
+        assert!(difference.amax() < 1e-2, "{:?}", srgb);
+    }
+
+    #[test]
+    fn test_cmyk_to_srgb() -> Result<(), Error> {
+        let profile = cmyk_profile()?;
+        let cmyk = |components| CMYK::new(components, profile.clone()).expect("It is CMYK.");
+        assert_srgb(&cmyk([0.0, 0.0, 0.0, 0.0]), [1.0, 1.0, 1.0]);
+        assert_srgb(&cmyk([1.0, 0.0, 0.0, 0.0]), [0.0, 1.0, 1.0]);
+        assert_srgb(&cmyk([0.0, 1.0, 1.0, 0.0]), [1.0, 0.0, 0.0]);
+        assert_srgb(&cmyk([0.2, 0.5, 0.9, 1.0]), [0.0, 0.0, 0.0]);
+        // Linear RGB of 0.5 is encoded as about 0.735 in sRGB.
+        assert_srgb(&cmyk([0.0, 0.0, 0.0, 0.5]), [0.735, 0.735, 0.735]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_srgb_to_cmyk_round_trip() -> Result<(), Error> {
+        let profile = cmyk_profile()?;
+        for hex in &["cd3131", "0dbc79", "2472c8", "808080"] {
+            let color = SRGB::from_hex(hex)?;
+            let cmyk = CMYK::from_color(&color, profile.clone()).expect("There is a BToA LUT.");
+            assert!(cmyk.components()[3].abs() < 1e-9);
+            let round_trip: SRGB = cmyk.to_color();
+            assert!((round_trip.to_vector3() - color.to_vector3()).amax() < 2e-3);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_to_profile_transform() -> Result<(), Error> {
+        let srgb = DisplayProfile {
+            description: String::from("sRGB"),
+            copyright: String::new(),
+            created: [2020, 1, 1, 0, 0, 0],
+            transform: SRGB::matrix_trc_transform().clone(),
+        };
+        let srgb = IccProfile::parse(&srgb.to_bytes())?;
+        let cmyk = IccProfile::parse(&build_profile(
+            &CMYK_SPACE,
+            &XYZ_SPACE,
+            &[(b"A2B0", a_to_b()), (b"B2A0", b_to_a())],
+        ))?;
+        for intent in [
+            RenderingIntent::Perceptual,
+            RenderingIntent::RelativeColorimetric,
+            RenderingIntent::Saturation,
+        ]
+        .iter()
+        {
+            let transform = IccTransform::new(&srgb, &cmyk, *intent, true)?;
+            let red = transform
+                .apply(&[1.0, 0.0, 0.0])
+                .expect("There is a BToA LUT.");
+            let expected = [0.0, 1.0, 1.0, 0.0];
+            assert!(red
+                .iter()
+                .zip(expected.iter())
+                .all(|(a, e)| (a - e).abs() < 1e-2));
+        }
+        let reverse = IccTransform::new(&cmyk, &srgb, RenderingIntent::Perceptual, false)?;
+        let blue = reverse
+            .apply(&[1.0, 1.0, 0.0, 0.0])
+            .expect("sRGB is a valid destination.");
+        assert!((Vector3::from_vec(blue) - Vector3::new(0.0, 0.0, 1.0)).amax() < 1e-2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_singular_chromatic_adaptation() -> Result<(), Error> {
+        let mut chad = b"sf32\0\0\0\0".to_vec();
+        chad.resize(8 + 9 * 4, 0);
+        let data = build_profile(
+            &CMYK_SPACE,
+            &XYZ_SPACE,
+            &[(b"A2B0", a_to_b()), (b"chad", chad)],
+        );
+        assert!(IccProfile::parse(&data)?
+            .lut_transform(RenderingIntent::Perceptual)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lab_connection_space() -> Result<(), Error> {
+        // A lut8Type mapping one gray channel from white at 0 to black at 1.
+        let mut a_to_b = b"mft1\0\0\0\0\x01\x03\x02\0".to_vec();
+        for value in [1, 0, 0, 0, 1, 0, 0, 0, 1].iter() {
+            a_to_b.extend_from_slice(&(value * 65536_i32).to_be_bytes());
+        }
+        a_to_b.extend((0..=255).map(|i| i as u8));
+        a_to_b.extend_from_slice(&[255, 128, 128, 0, 128, 128]);
+        for _ in 0..3 {
+            a_to_b.extend((0..=255).map(|i| i as u8));
+        }
+        let data = build_profile(b"GRAY", &LAB_SPACE, &[(b"A2B0", a_to_b)]);
+        let profile = IccProfile::parse(&data)?.lut_transform(RenderingIntent::Perceptual)?;
+        assert!(profile.from_pcs.is_none());
+        assert!(CMYK::new([0.0; 4], Arc::new(profile.clone())).is_none());
+
+        let white: CIELAB = profile.to_color(&[0.0]);
+        let expected = Vector3::new(1.0, 0.0, 0.0);
+        assert!((white.to_vector3() - expected).amax() < 1e-3);
+        let gray: CIELAB = profile.to_color(&[0.5]);
+        assert!((gray.to_vector3()[0] - 0.5).abs() < 3e-3);
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use nalgebra::{Matrix3, Vector3};
+
+use super::profile::profile_id;
+use super::{Signature, D50, DISPLAY_CLASS, RGB_SPACE, XYZ_SPACE};
+use crate::colors::{MatrixTRCTransform, ToneResponseCurve};
+
+/// The number of samples used for curves that are neither parametric nor tabulated.
+const CURVE_SAMPLES: usize = 4096;
+
+/// An ICC version 4 display profile for an RGB color space defined by a matrix and tone response
+/// curves.
+///
+/// The transform should map to the D50 profile connection space, which means that its matrix
+/// contains the chromatically adapted colorants, and its chromatic adaptation matrix maps from
+/// the white point of the device to D50.
+#[derive(Clone, Debug)]
+pub struct DisplayProfile {
+    pub description: String,
+    pub copyright: String,
+    /// The creation date and time as (year, month, day, hours, minutes, seconds), in UTC.
+    pub created: [u16; 6],
+    pub transform: MatrixTRCTransform,
+}
+
+impl DisplayProfile {
+    /// Serialize the profile, including its profile ID.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let transform = &self.transform;
+        let matrix = transform.matrix();
+        let tags: Vec<(&Signature, Vec<u8>)> = vec![
+            (b"desc", text_tag(&self.description)),
+            (b"cprt", text_tag(&self.copyright)),
+            (b"wtpt", xyz_tag(Vector3::from(D50))),
+            (b"chad", matrix_tag(transform.chromatic_adaptation_matrix())),
+            (b"rXYZ", xyz_tag(matrix.column(0).into_owned())),
+            (b"gXYZ", xyz_tag(matrix.column(1).into_owned())),
+            (b"bXYZ", xyz_tag(matrix.column(2).into_owned())),
+            (b"rTRC", curve_tag(transform.red_trc().as_ref())),
+            (b"gTRC", curve_tag(transform.green_trc().as_ref())),
+            (b"bTRC", curve_tag(transform.blue_trc().as_ref())),
+        ];
+
+        // Identical tags, typically the three tone response curves, share their data.
+        let table_end = 128 + 4 + 12 * tags.len();
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut body = Vec::new();
+        let mut offsets: HashMap<&[u8], usize> = HashMap::new();
+        for (signature, data) in tags.iter() {
+            let tag_offset = *offsets.entry(data.as_slice()).or_insert_with(|| {
+                let tag_offset = table_end + body.len();
+                body.extend_from_slice(data);
+                body.resize(body.len().div_ceil(4) * 4, 0);
+                tag_offset
+            });
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&(tag_offset as u32).to_be_bytes());
+            table.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        }
+
+        let mut header = vec![0; 128];
+        let size = 128 + table.len() + body.len();
+        header[0..4].copy_from_slice(&(size as u32).to_be_bytes());
+        header[8] = 4;
+        header[9] = 0x40;
+        header[12..16].copy_from_slice(&DISPLAY_CLASS);
+        header[16..20].copy_from_slice(&RGB_SPACE);
+        header[20..24].copy_from_slice(&XYZ_SPACE);
+        for (i, value) in self.created.iter().enumerate() {
+            header[24 + 2 * i..26 + 2 * i].copy_from_slice(&value.to_be_bytes());
+        }
+        header[36..40].copy_from_slice(b"acsp");
+        header[68..80].copy_from_slice(&xyz_number(Vector3::from(D50)));
+
+        let mut data = [header, table, body].concat();
+        let id = profile_id(&data);
+        data[84..100].copy_from_slice(&id);
+        data
+    }
+}
+
+fn s15_fixed16(value: f64) -> [u8; 4] {
+    let fixed = (value * 65536.0)
+        .round()
+        .clamp(f64::from(i32::MIN), f64::from(i32::MAX));
+    (fixed as i32).to_be_bytes()
+}
+
+fn xyz_number(xyz: Vector3<f64>) -> Vec<u8> {
+    xyz.iter().flat_map(|v| s15_fixed16(*v).to_vec()).collect()
+}
+
+fn xyz_tag(xyz: Vector3<f64>) -> Vec<u8> {
+    [b"XYZ \0\0\0\0".to_vec(), xyz_number(xyz)].concat()
+}
+
+fn matrix_tag(matrix: &Matrix3<f64>) -> Vec<u8> {
+    let mut data = b"sf32\0\0\0\0".to_vec();
+    for row in matrix.row_iter() {
+        for value in row.iter() {
+            data.extend_from_slice(&s15_fixed16(*value));
+        }
+    }
+    data
+}
+
+/// Encode text as a multiLocalizedUnicodeType with a single US English record.
+fn text_tag(text: &str) -> Vec<u8> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    let mut data = b"mluc\0\0\0\0".to_vec();
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(&12u32.to_be_bytes());
+    data.extend_from_slice(b"enUS");
+    data.extend_from_slice(&(2 * units.len() as u32).to_be_bytes());
+    data.extend_from_slice(&28u32.to_be_bytes());
+    for unit in units {
+        data.extend_from_slice(&unit.to_be_bytes());
+    }
+    data
+}
+
+/// Encode a curve as a parametricCurveType if possible, or as a tabulated curveType otherwise.
+fn curve_tag(curve: &dyn ToneResponseCurve) -> Vec<u8> {
+    if let Some((function_type, parameters)) = curve.parametric() {
+        let mut data = b"para\0\0\0\0".to_vec();
+        data.extend_from_slice(&function_type.to_be_bytes());
+        data.extend_from_slice(&[0, 0]);
+        for parameter in parameters {
+            data.extend_from_slice(&s15_fixed16(parameter));
+        }
+        return data;
+    }
+    let samples = match curve.samples() {
+        Some(samples) => samples.to_vec(),
+        None => (0..CURVE_SAMPLES)
+            .map(|i| curve.apply(i as f64 / (CURVE_SAMPLES - 1) as f64))
+            .collect(),
+    };
+    let mut data = b"curv\0\0\0\0".to_vec();
+    data.extend_from_slice(&(samples.len() as u32).to_be_bytes());
+    for sample in samples {
+        let value = (sample.clamp(0.0, 1.0) * 65535.0).round() as u16;
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::colors::{Color, ParametricCurveType3, SampledCurve, CIEXYZ, SRGB};
+    use crate::errors::Error;
+    use crate::icc::IccProfile;
+
+    fn srgb_profile() -> DisplayProfile {
+        let curve = Arc::new(ParametricCurveType3 {
+            g: 157_286.0 / 65536.0,
+            a: 62119.0 / 65536.0,
+            b: 3417.0 / 65536.0,
+            c: 5072.0 / 65536.0,
+            d: 2651.0 / 65536.0,
+        });
+        #[rustfmt::skip]
+        let matrix = Matrix3::new(
+            28578.0, 25241.0,  9376.0,
+            14581.0, 46981.0,  3972.0,
+              912.0,  6362.0, 46799.0,
+        ) / 65536.0;
+        #[rustfmt::skip]
+        let chromatic_adaptation_matrix = Matrix3::new(
+            68674.0,  1502.0, -3290.0,
+             1939.0, 64912.0, -1118.0,
+             -605.0,   988.0, 49262.0,
+        ) / 65536.0;
+        DisplayProfile {
+            description: String::from("sRGB IEC61966-2.1 \u{2013} test"),
+            copyright: String::from("No copyright, use freely"),
+            created: [2020, 3, 14, 15, 9, 26],
+            transform: MatrixTRCTransform::new(
+                matrix,
+                curve.clone(),
+                curve.clone(),
+                curve,
+                chromatic_adaptation_matrix,
+            )
+            .expect("The matrices are invertible."),
+        }
+    }
+
+    #[test]
+    fn test_header_and_profile_id() -> Result<(), Error> {
+        let data = srgb_profile().to_bytes();
+        assert_eq!(data.len() % 4, 0);
+        let profile = IccProfile::parse(&data)?;
+        assert_eq!(profile.header.size as usize, data.len());
+        assert_eq!(profile.header.version, (4, 0x40));
+        assert_eq!(profile.header.device_class, DISPLAY_CLASS);
+        assert_eq!(profile.header.created, [2020, 3, 14, 15, 9, 26]);
+        assert_eq!(
+            &data[68..80],
+            &[0, 0, 0xf6, 0xd6, 0, 1, 0, 0, 0, 0, 0xd3, 0x2d]
+        );
+        assert_ne!(profile.header.profile_id, [0; 16]);
+        assert_eq!(profile.header.profile_id, profile.compute_profile_id());
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_is_byte_exact() -> Result<(), Error> {
+        let original = srgb_profile();
+        let data = original.to_bytes();
+        let parsed = IccProfile::parse(&data)?.display_profile()?;
+        assert_eq!(parsed.description, original.description);
+        assert_eq!(parsed.copyright, original.copyright);
+        assert_eq!(parsed.transform.matrix(), original.transform.matrix());
+        assert_eq!(parsed.to_bytes(), data);
+
+        for hex in &["ff0000", "00ff00", "0000ff", "808080", "123456"] {
+            let srgb = SRGB::from_hex(hex)?;
+            let xyz: CIEXYZ = parsed.transform.to_color(srgb.to_vector3());
+            assert!((xyz.to_vector3() - srgb.to_ciexyz().to_vector3()).amax() < 1e-12);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sampled_curves_round_trip() -> Result<(), Error> {
+        let mut profile = srgb_profile();
+        let samples = (0..=255).map(|i| f64::from(i) / 255.0).collect();
+        let transform = &profile.transform;
+        profile.transform = transform.clone().with_trcs(
+            transform.red_trc().clone(),
+            Arc::new(SampledCurve::new(samples).expect("There are enough samples.")),
+            transform.blue_trc().clone(),
+        );
+        let data = profile.to_bytes();
+        let parsed = IccProfile::parse(&data)?;
+        let transform = parsed.matrix_trc_transform()?;
+        assert_eq!(transform.green_trc().samples().map(|s| s.len()), Some(256));
+        assert!((transform.green_trc().apply(0.3) - 0.3).abs() < 1e-9);
+        assert_eq!(parsed.display_profile()?.to_bytes(), data);
+        Ok(())
+    }
+}
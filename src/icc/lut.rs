@@ -0,0 +1,669 @@
+use std::sync::Arc;
+
+use nalgebra::{Matrix3, Vector3};
+
+use super::profile::{
+    parse_curve, read_bytes, read_s15_fixed16, read_signature, read_u16, read_u32, signature_string,
+};
+use super::{Signature, D50, XYZ_SPACE};
+use crate::colors::{Color, SampledCurve, ToneResponseCurve, CIEXYZ};
+use crate::errors::Error;
+use crate::errors::Error::IccParseError;
+
+/// The largest number of input or output channels of a LUT, as limited by the ICC specification.
+const MAX_CHANNELS: usize = 15;
+
+/// A method of interpolating between the grid points of a color lookup table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Interpolate linearly along each input dimension in turn. This is trilinear interpolation
+    /// for three inputs, and its generalization for other numbers of inputs.
+    Trilinear,
+    /// Split each cell of the last three input dimensions into six tetrahedra, and interpolate
+    /// within the one containing the input. Any preceding dimensions, such as the cyan of a CMYK
+    /// table, are interpolated linearly. This is smoother along the neutral axis, and cheaper.
+    Tetrahedral,
+}
+
+/// A multidimensional color lookup table with evenly spaced grid points over [0, 1] on each
+/// input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clut {
+    grid_points: Vec<usize>,
+    output_channels: usize,
+    values: Vec<f64>,
+}
+
+impl Clut {
+    /// Create a lookup table from the number of grid points on each input, and the output values
+    /// at each grid point. The first input varies slowest, and the outputs of each grid point are
+    /// stored together.
+    ///
+    /// Returns `None` if a dimension has fewer than two grid points, or the number of values does
+    /// not match the size of the grid.
+    pub fn new(grid_points: Vec<usize>, output_channels: usize, values: Vec<f64>) -> Option<Self> {
+        if grid_points.is_empty()
+            || grid_points.len() > MAX_CHANNELS
+            || grid_points.iter().any(|g| *g < 2)
+            || output_channels == 0
+        {
+            return None;
+        }
+        let size = grid_points
+            .iter()
+            .try_fold(output_channels, |size, g| size.checked_mul(*g))?;
+        if values.len() != size {
+            return None;
+        }
+        Some(Clut {
+            grid_points,
+            output_channels,
+            values,
+        })
+    }
+
+    /// The number of grid points on each input.
+    pub fn grid_points(&self) -> &[usize] {
+        &self.grid_points
+    }
+
+    /// Look up and interpolate the outputs for an input. Inputs are clamped to [0, 1].
+    pub fn evaluate(&self, input: &[f64], interpolation: Interpolation) -> Vec<f64> {
+        let mut strides = vec![self.output_channels; self.grid_points.len()];
+        for i in (0..self.grid_points.len() - 1).rev() {
+            strides[i] = strides[i + 1] * self.grid_points[i + 1];
+        }
+        let cells: Vec<(usize, f64)> = input
+            .iter()
+            .zip(self.grid_points.iter())
+            .map(|(x, g)| {
+                let position = x.clamp(0.0, 1.0) * (g - 1) as f64;
+                let i = (position.floor() as usize).min(g - 2);
+                (i, position - i as f64)
+            })
+            .collect();
+        let mut output = vec![0.0; self.output_channels];
+        self.interpolate(&cells, &strides, 0, 0, 1.0, interpolation, &mut output);
+        output
+    }
+
+    /// Accumulate the weighted outputs of the cell containing the input, starting at a dimension.
+    #[allow(clippy::too_many_arguments)]
+    fn interpolate(
+        &self,
+        cells: &[(usize, f64)],
+        strides: &[usize],
+        dimension: usize,
+        base: usize,
+        weight: f64,
+        interpolation: Interpolation,
+        output: &mut [f64],
+    ) {
+        let remaining = cells.len() - dimension;
+        if remaining == 0 {
+            for (o, v) in output.iter_mut().zip(&self.values[base..]) {
+                *o += weight * v;
+            }
+            return;
+        }
+        if remaining == 3 && interpolation == Interpolation::Tetrahedral {
+            let base = base
+                + (dimension..cells.len())
+                    .map(|d| cells[d].0 * strides[d])
+                    .sum::<usize>();
+            self.tetrahedral(
+                &cells[dimension..],
+                &strides[dimension..],
+                base,
+                weight,
+                output,
+            );
+            return;
+        }
+        let (i, t) = cells[dimension];
+        let base = base + i * strides[dimension];
+        let next = dimension + 1;
+        self.interpolate(
+            cells,
+            strides,
+            next,
+            base,
+            weight * (1.0 - t),
+            interpolation,
+            output,
+        );
+        if t > 0.0 {
+            let base = base + strides[dimension];
+            self.interpolate(
+                cells,
+                strides,
+                next,
+                base,
+                weight * t,
+                interpolation,
+                output,
+            );
+        }
+    }
+
+    fn tetrahedral(
+        &self,
+        cells: &[(usize, f64)],
+        strides: &[usize],
+        base: usize,
+        weight: f64,
+        output: &mut [f64],
+    ) {
+        let (rx, ry, rz) = (cells[0].1, cells[1].1, cells[2].1);
+        let (sx, sy, sz) = (strides[0], strides[1], strides[2]);
+        // The vertices along the path from the first to the last corner of the tetrahedron, and
+        // the distance travelled along each edge of the path.
+        let (first, second, third) = if rx >= ry {
+            if ry >= rz {
+                ((sx, rx), (sx + sy, ry), rz)
+            } else if rx >= rz {
+                ((sx, rx), (sx + sz, rz), ry)
+            } else {
+                ((sz, rz), (sx + sz, rx), ry)
+            }
+        } else if rx >= rz {
+            ((sy, ry), (sx + sy, rx), rz)
+        } else if ry >= rz {
+            ((sy, ry), (sy + sz, rz), rx)
+        } else {
+            ((sz, rz), (sy + sz, ry), rx)
+        };
+        let corners = [
+            (0, 1.0 - first.1),
+            (first.0, first.1 - second.1),
+            (second.0, second.1 - third),
+            (sx + sy + sz, third),
+        ];
+        for (offset, w) in corners.iter() {
+            if *w == 0.0 {
+                continue;
+            }
+            let values = &self.values[base + offset..];
+            for (o, v) in output.iter_mut().zip(values) {
+                *o += weight * w * v;
+            }
+        }
+    }
+}
+
+/// A stage of the processing pipeline of a LUT.
+#[derive(Clone, Debug)]
+pub enum LutStage {
+    /// A one-dimensional curve for each channel.
+    Curves(Vec<Arc<dyn ToneResponseCurve>>),
+    /// A 3x3 matrix followed by an offset, applied to three channels.
+    Matrix {
+        matrix: Matrix3<f64>,
+        offset: Vector3<f64>,
+    },
+    /// A multidimensional color lookup table.
+    Clut(Clut),
+}
+
+/// The encoding of the profile connection space values at the PCS side of a LUT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PcsEncoding {
+    /// The encoding of lutAtoBType, lutBtoAType and lut8Type, where L* = 100 and a*, b* = 127
+    /// are encoded as 1.
+    Standard,
+    /// The legacy encoding of lut16Type and version 2 profiles, where L* = 100 and a*, b* =
+    /// 127 are encoded as 0xFF00 / 0xFFFF.
+    Legacy,
+}
+
+/// A transform between a device space and the profile connection space, as defined by an ICC
+/// lut8Type, lut16Type, lutAtoBType or lutBtoAType tag.
+///
+/// Inputs and outputs are normalized to [0, 1].
+#[derive(Clone, Debug)]
+pub struct Lut {
+    pub input_channels: usize,
+    pub output_channels: usize,
+    pub stages: Vec<LutStage>,
+    pub pcs_encoding: PcsEncoding,
+}
+
+impl Lut {
+    /// Parse a tag of lut8Type, lut16Type, lutAtoBType or lutBtoAType.
+    ///
+    /// The matrix of lut8Type and lut16Type is only used when the input is PCS XYZ, which the
+    /// caller indicates with `xyz_input`.
+    pub fn parse(data: &[u8], xyz_input: bool) -> Result<Self, Error> {
+        let tag_type = read_signature(data, 0)?;
+        let header = read_bytes(data, 8, 4)?;
+        let input_channels = usize::from(header[0]);
+        let output_channels = usize::from(header[1]);
+        if !(1..=MAX_CHANNELS).contains(&input_channels)
+            || !(1..=MAX_CHANNELS).contains(&output_channels)
+        {
+            return Err(IccParseError(format!(
+                "Invalid number of channels, {} in and {} out",
+                input_channels, output_channels
+            )));
+        }
+        let stages = match &tag_type {
+            b"mft1" | b"mft2" => {
+                parse_lut8_lut16(data, input_channels, output_channels, xyz_input)?
+            }
+            b"mAB " => parse_lut_a_to_b(data, input_channels, output_channels, false)?,
+            b"mBA " => parse_lut_a_to_b(data, input_channels, output_channels, true)?,
+            other => {
+                return Err(IccParseError(format!(
+                    "Expected a LUT, got type {}",
+                    signature_string(other)
+                )))
+            }
+        };
+        Ok(Lut {
+            input_channels,
+            output_channels,
+            stages,
+            pcs_encoding: if &tag_type == b"mft2" {
+                PcsEncoding::Legacy
+            } else {
+                PcsEncoding::Standard
+            },
+        })
+    }
+
+    /// Apply each stage of the LUT to an input.
+    pub fn apply(&self, input: &[f64], interpolation: Interpolation) -> Vec<f64> {
+        let mut values = input.to_vec();
+        for stage in &self.stages {
+            values = match stage {
+                LutStage::Curves(curves) => values
+                    .iter()
+                    .zip(curves.iter())
+                    .map(|(v, curve)| curve.apply(v.clamp(0.0, 1.0)))
+                    .collect(),
+                LutStage::Matrix { matrix, offset } => {
+                    let result = matrix * Vector3::new(values[0], values[1], values[2]) + offset;
+                    result.iter().copied().collect()
+                }
+                LutStage::Clut(clut) => clut.evaluate(&values, interpolation),
+            };
+        }
+        values
+    }
+}
+
+/// A transform between the device space of a LUT-based profile, such as a CMYK printer profile,
+/// and CIE XYZ.
+#[derive(Clone, Debug)]
+pub struct LutTransform {
+    /// The AToB LUT, from the device space to the profile connection space.
+    pub to_pcs: Lut,
+    /// The BToA LUT, from the profile connection space to the device space, if the profile has
+    /// one. Input-only profiles, such as those of scanners, may not.
+    pub from_pcs: Option<Lut>,
+    /// The profile connection space, either `XYZ_SPACE` or `LAB_SPACE`.
+    pub connection_space: Signature,
+    /// Chromatically adapt white point in PCS XYZ
+    pub chromatic_adaptation_matrix: Matrix3<f64>,
+    /// The inverse of `chromatic_adaptation_matrix`, from PCS XYZ back to CIE XYZ.
+    pub chromatic_adaptation_inverse: Matrix3<f64>,
+    pub interpolation: Interpolation,
+}
+
+impl LutTransform {
+    /// Transform from device space to PCS XYZ
+    pub fn to_profile_connection_space(&self, device: &[f64]) -> Vector3<f64> {
+        let pcs = self.to_pcs.apply(device, self.interpolation);
+        decode_pcs(&pcs, self.connection_space, self.to_pcs.pcs_encoding)
+    }
+
+    /// Transform from PCS XYZ to device space, or `None` if the profile has no BToA LUT.
+    pub fn to_device_space(&self, v: Vector3<f64>) -> Option<Vec<f64>> {
+        let lut = self.from_pcs.as_ref()?;
+        let pcs = encode_pcs(v, self.connection_space, lut.pcs_encoding);
+        Some(lut.apply(&pcs, self.interpolation))
+    }
+
+    /// Convert device components to a color.
+    pub fn to_color<C>(&self, device: &[f64]) -> C
+    where
+        C: Color,
+    {
+        let xyz = self.chromatic_adaptation_inverse * self.to_profile_connection_space(device);
+        C::from_ciexyz(&CIEXYZ::from_vector3(xyz))
+    }
+
+    /// Convert a color to device components, or `None` if the profile has no BToA LUT.
+    pub fn from_color<C>(&self, color: &C) -> Option<Vec<f64>>
+    where
+        C: Color,
+    {
+        self.to_device_space(self.chromatic_adaptation_matrix * color.to_ciexyz().to_vector3())
+    }
+}
+
+/// Decode normalized PCS values to D50-relative XYZ.
+fn decode_pcs(values: &[f64], space: Signature, encoding: PcsEncoding) -> Vector3<f64> {
+    let v = Vector3::new(values[0], values[1], values[2]);
+    if space == XYZ_SPACE {
+        return v * (65535.0 / 32768.0);
+    }
+    let v = match encoding {
+        PcsEncoding::Standard => v,
+        PcsEncoding::Legacy => v * (65535.0 / 65280.0),
+    };
+    let (l, a, b) = (v[0] * 100.0, v[1] * 255.0 - 128.0, v[2] * 255.0 - 128.0);
+    let fy = (l + 16.0) / 116.0;
+    let f = Vector3::new(fy + a / 500.0, fy, fy - b / 200.0);
+    let inverse = |t: f64| {
+        if t > 6.0 / 29.0 {
+            t.powi(3)
+        } else {
+            3.0 * (6.0_f64 / 29.0).powi(2) * (t - 4.0 / 29.0)
+        }
+    };
+    f.map(inverse).component_mul(&Vector3::from(D50))
+}
+
+/// Encode D50-relative XYZ as normalized PCS values.
+fn encode_pcs(xyz: Vector3<f64>, space: Signature, encoding: PcsEncoding) -> Vec<f64> {
+    if space == XYZ_SPACE {
+        return (xyz * (32768.0 / 65535.0)).iter().copied().collect();
+    }
+    let f = |t: f64| {
+        if t > (6.0_f64 / 29.0).powi(3) {
+            t.cbrt()
+        } else {
+            t / (3.0 * (6.0_f64 / 29.0).powi(2)) + 4.0 / 29.0
+        }
+    };
+    let f = xyz.component_div(&Vector3::from(D50)).map(f);
+    let l = 116.0 * f[1] - 16.0;
+    let a = 500.0 * (f[0] - f[1]);
+    let b = 200.0 * (f[1] - f[2]);
+    let v = Vector3::new(l / 100.0, (a + 128.0) / 255.0, (b + 128.0) / 255.0);
+    let v = match encoding {
+        PcsEncoding::Standard => v,
+        PcsEncoding::Legacy => v * (65280.0 / 65535.0),
+    };
+    v.iter().copied().collect()
+}
+
+fn parse_lut8_lut16(
+    data: &[u8],
+    input_channels: usize,
+    output_channels: usize,
+    xyz_input: bool,
+) -> Result<Vec<LutStage>, Error> {
+    let grid = usize::from(read_bytes(data, 10, 1)?[0]);
+    let mut matrix = [0.0; 9];
+    for (i, value) in matrix.iter_mut().enumerate() {
+        *value = read_s15_fixed16(data, 12 + 4 * i)?;
+    }
+    let matrix = Matrix3::from_row_slice(&matrix);
+
+    let (input_entries, output_entries, width, mut position) = if &data[0..4] == b"mft1" {
+        (256, 256, 1, 48)
+    } else {
+        let input_entries = usize::from(read_u16(data, 48)?);
+        let output_entries = usize::from(read_u16(data, 50)?);
+        (input_entries, output_entries, 2, 52)
+    };
+    let tables = |position: &mut usize, channels: usize, entries: usize| {
+        let curves = (0..channels)
+            .map(|_| {
+                let values = read_normalized(data, position, entries, width)?;
+                let curve = SampledCurve::new(values).ok_or_else(|| {
+                    IccParseError(format!("Invalid table with {} entries", entries))
+                })?;
+                Ok(Arc::new(curve) as Arc<dyn ToneResponseCurve>)
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok::<_, Error>(LutStage::Curves(curves))
+    };
+
+    let mut stages = Vec::new();
+    if xyz_input && input_channels == 3 && matrix != Matrix3::identity() {
+        stages.push(LutStage::Matrix {
+            matrix,
+            offset: Vector3::zeros(),
+        });
+    }
+    stages.push(tables(&mut position, input_channels, input_entries)?);
+    let grid_points = vec![grid; input_channels];
+    let size = grid_size(&grid_points, output_channels)?;
+    let values = read_normalized(data, &mut position, size, width)?;
+    let clut = Clut::new(grid_points, output_channels, values)
+        .ok_or_else(|| IccParseError(format!("Invalid lookup table with {} grid points", grid)))?;
+    stages.push(LutStage::Clut(clut));
+    stages.push(tables(&mut position, output_channels, output_entries)?);
+    Ok(stages)
+}
+
+/// The number of values in a lookup table.
+fn grid_size(grid_points: &[usize], output_channels: usize) -> Result<usize, Error> {
+    grid_points
+        .iter()
+        .try_fold(output_channels, |size, g| size.checked_mul(*g))
+        .ok_or_else(|| IccParseError(String::from("The lookup table is too large")))
+}
+
+/// Read unsigned integers of one or two bytes, normalized to [0, 1], and advance the position.
+fn read_normalized(
+    data: &[u8],
+    position: &mut usize,
+    count: usize,
+    width: usize,
+) -> Result<Vec<f64>, Error> {
+    let length = count
+        .checked_mul(width)
+        .ok_or_else(|| IccParseError(String::from("The table is too large")))?;
+    let bytes = read_bytes(data, *position, length)?;
+    *position += length;
+    Ok(if width == 1 {
+        bytes.iter().map(|b| f64::from(*b) / 255.0).collect()
+    } else {
+        bytes
+            .chunks(2)
+            .map(|pair| f64::from(u16::from_be_bytes([pair[0], pair[1]])) / 65535.0)
+            .collect()
+    })
+}
+
+/// Parse a lutAtoBType, or a lutBtoAType if `b_to_a` is set. Both have the same layout, but the
+/// stages are applied in the opposite order.
+fn parse_lut_a_to_b(
+    data: &[u8],
+    input_channels: usize,
+    output_channels: usize,
+    b_to_a: bool,
+) -> Result<Vec<LutStage>, Error> {
+    let offset = |i: usize| -> Result<usize, Error> { Ok(read_u32(data, 12 + 4 * i)? as usize) };
+    let (b_offset, matrix_offset, m_offset, clut_offset, a_offset) =
+        (offset(0)?, offset(1)?, offset(2)?, offset(3)?, offset(4)?);
+    // The A curves and the lookup table are on the device side, and the B curves on the PCS side.
+    let (a_channels, b_channels) = if b_to_a {
+        (output_channels, input_channels)
+    } else {
+        (input_channels, output_channels)
+    };
+
+    let curves = |offset: usize, channels: usize| -> Result<Option<LutStage>, Error> {
+        if offset == 0 {
+            return Ok(None);
+        }
+        let mut position = offset;
+        let mut curves = Vec::with_capacity(channels);
+        for _ in 0..channels {
+            let (curve, length) = parse_curve(data.get(position..).unwrap_or(&[]))?;
+            curves.push(curve);
+            position += length.div_ceil(4) * 4;
+        }
+        Ok(Some(LutStage::Curves(curves)))
+    };
+    let a_curves = curves(a_offset, a_channels)?;
+    let m_curves = curves(m_offset, 3)?;
+    let b_curves = curves(b_offset, b_channels)?;
+    if b_curves.is_none() {
+        return Err(IccParseError(String::from("Missing the required B curves")));
+    }
+
+    let matrix = if matrix_offset == 0 {
+        None
+    } else if b_channels != 3 {
+        return Err(IccParseError(format!(
+            "A matrix requires 3 PCS channels, got {}",
+            b_channels
+        )));
+    } else {
+        let mut values = [0.0; 12];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = read_s15_fixed16(data, matrix_offset + 4 * i)?;
+        }
+        Some(LutStage::Matrix {
+            matrix: Matrix3::from_row_slice(&values[..9]),
+            offset: Vector3::new(values[9], values[10], values[11]),
+        })
+    };
+
+    let clut = if clut_offset == 0 {
+        if input_channels != output_channels {
+            return Err(IccParseError(format!(
+                "Without a lookup table, {} input channels cannot map to {} output channels",
+                input_channels, output_channels
+            )));
+        }
+        None
+    } else {
+        let (clut_inputs, clut_outputs) = if b_to_a {
+            (3, output_channels)
+        } else {
+            (input_channels, 3)
+        };
+        let grid_points: Vec<usize> = read_bytes(data, clut_offset, clut_inputs)?
+            .iter()
+            .map(|g| usize::from(*g))
+            .collect();
+        let precision = usize::from(read_bytes(data, clut_offset + 16, 1)?[0]);
+        if precision != 1 && precision != 2 {
+            return Err(IccParseError(format!(
+                "Invalid lookup table precision {}",
+                precision
+            )));
+        }
+        let size = grid_size(&grid_points, clut_outputs)?;
+        let values = read_normalized(data, &mut (clut_offset + 20), size, precision)?;
+        let clut = Clut::new(grid_points, clut_outputs, values)
+            .ok_or_else(|| IccParseError(String::from("Invalid lookup table grid")))?;
+        Some(LutStage::Clut(clut))
+    };
+
+    let stages: Vec<LutStage> = if b_to_a {
+        vec![b_curves, matrix, m_curves, clut, a_curves]
+    } else {
+        vec![a_curves, clut, m_curves, matrix, b_curves]
+    }
+    .into_iter()
+    .flatten()
+    .collect();
+    Ok(stages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_clut(grid: usize) -> Clut {
+        // Three outputs, each a different linear function of three inputs.
+        let mut values = Vec::new();
+        for i in 0..grid {
+            for j in 0..grid {
+                for k in 0..grid {
+                    let scale = (grid - 1) as f64;
+                    let (x, y, z) = (i as f64 / scale, j as f64 / scale, k as f64 / scale);
+                    values.extend_from_slice(&[
+                        0.2 * x + 0.5 * y + 0.3 * z,
+                        x,
+                        0.9 - 0.4 * z + 0.1 * y,
+                    ]);
+                }
+            }
+        }
+        Clut::new(vec![grid; 3], 3, values).expect("The grid is valid.")
+    }
+
+    #[test]
+    fn test_interpolation_of_linear_functions_is_exact() {
+        let clut = linear_clut(5);
+        let (x, y, z) = (0.13, 0.77, 0.42);
+        let expected = [0.2 * x + 0.5 * y + 0.3 * z, x, 0.9 - 0.4 * z + 0.1 * y];
+        for interpolation in [Interpolation::Trilinear, Interpolation::Tetrahedral].iter() {
+            let output = clut.evaluate(&[x, y, z], *interpolation);
+            for (o, e) in output.iter().zip(expected.iter()) {
+                assert!((o - e).abs() < 1e-12, "{:?}", interpolation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tetrahedral_differs_from_trilinear() {
+        // A single cell with a nonlinear function, x * y * z.
+        let mut values = vec![0.0; 8];
+        values[7] = 1.0;
+        let clut = Clut::new(vec![2, 2, 2], 1, values).expect("The grid is valid.");
+        let input = [0.5, 0.5, 0.5];
+        assert!((clut.evaluate(&input, Interpolation::Trilinear)[0] - 0.125).abs() < 1e-12);
+        assert!((clut.evaluate(&input, Interpolation::Tetrahedral)[0] - 0.5).abs() < 1e-12);
+        // Both agree at the grid points.
+        for interpolation in [Interpolation::Trilinear, Interpolation::Tetrahedral].iter() {
+            assert_eq!(clut.evaluate(&[1.0, 1.0, 1.0], *interpolation), vec![1.0]);
+            assert_eq!(clut.evaluate(&[1.0, 1.0, 0.0], *interpolation), vec![0.0]);
+        }
+    }
+
+    #[test]
+    fn test_four_dimensional_clut() {
+        // The output is the sum of the inputs divided by 4, which is linear.
+        let grid = 3;
+        let mut values = Vec::new();
+        for i in 0..grid * grid * grid * grid {
+            let digits = [i / 27, i / 9 % 3, i / 3 % 3, i % 3];
+            values.push(digits.iter().sum::<usize>() as f64 / 8.0);
+        }
+        let clut = Clut::new(vec![grid; 4], 1, values).expect("The grid is valid.");
+        let input = [0.1, 0.9, 0.35, 0.6];
+        for interpolation in [Interpolation::Trilinear, Interpolation::Tetrahedral].iter() {
+            let output = clut.evaluate(&input, *interpolation)[0];
+            assert!((output - 0.4875).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_invalid_cluts() {
+        assert!(Clut::new(vec![2, 2], 1, vec![0.0; 3]).is_none());
+        assert!(Clut::new(vec![1, 2], 1, vec![0.0; 2]).is_none());
+        assert!(Clut::new(vec![], 1, vec![]).is_none());
+    }
+
+    #[test]
+    fn test_a_to_b_without_clut_requires_matching_channels() {
+        let para = |data: &mut Vec<u8>| {
+            data.extend_from_slice(b"para\0\0\0\0\0\0\0\0");
+            data.extend_from_slice(&65536_i32.to_be_bytes());
+        };
+        let build = |input_channels: u8| {
+            let mut data = b"mAB \0\0\0\0".to_vec();
+            data.extend_from_slice(&[input_channels, 3, 0, 0]);
+            let a_offset = 32 + 16 * 3_u32;
+            for offset in [32, 0, 0, 0, a_offset].iter() {
+                data.extend_from_slice(&offset.to_be_bytes());
+            }
+            for _ in 0..3 + input_channels {
+                para(&mut data);
+            }
+            data
+        };
+        assert!(Lut::parse(&build(3), false).is_ok());
+        assert!(Lut::parse(&build(1), false).is_err());
+    }
+}
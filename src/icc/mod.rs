@@ -0,0 +1,17 @@
+mod cmyk;
+mod display_profile;
+mod lut;
+mod md5;
+mod profile;
+mod transform;
+
+pub use cmyk::CMYK;
+pub use display_profile::DisplayProfile;
+pub use lut::{Clut, Interpolation, Lut, LutStage, LutTransform, PcsEncoding};
+pub use profile::{
+    IccHeader, IccProfile, Signature, CMYK_SPACE, DISPLAY_CLASS, LAB_SPACE, RGB_SPACE, XYZ_SPACE,
+};
+pub use transform::{DeviceTransform, IccTransform, RenderingIntent};
+
+/// The D50 illuminant of the profile connection space.
+pub(crate) const D50: [f64; 3] = [0.9642, 1.0, 0.8249];
@@ -0,0 +1,744 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use nalgebra::{Matrix3, Vector3};
+
+use super::md5::md5;
+use super::{DisplayProfile, Interpolation, Lut, LutTransform, RenderingIntent};
+use crate::colors::{
+    get_d65_ciexyz, Color, MatrixTRCTransform, ParametricCurveType0, ParametricCurveType1,
+    ParametricCurveType2, ParametricCurveType3, ParametricCurveType4, SampledCurve,
+    ToneResponseCurve,
+};
+use crate::errors::Error;
+use crate::errors::Error::IccParseError;
+
+/// The size of the fixed ICC profile header, in bytes.
+const HEADER_SIZE: usize = 128;
+
+/// A four-byte ICC signature, such as a tag, type, or color space signature.
+pub type Signature = [u8; 4];
+
+/// The profile/device class of a display profile.
+pub const DISPLAY_CLASS: Signature = *b"mntr";
+pub const RGB_SPACE: Signature = *b"RGB ";
+pub const XYZ_SPACE: Signature = *b"XYZ ";
+pub const LAB_SPACE: Signature = *b"Lab ";
+pub const CMYK_SPACE: Signature = *b"CMYK";
+
+/// The fields of an ICC profile header.
+///
+/// See the ICC specification (ICC.1:2010, section 7.2) for the meaning of each field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IccHeader {
+    pub size: u32,
+    pub preferred_cmm: Signature,
+    /// The major version, and the minor and bug fix versions encoded as two nibbles. For
+    /// example, version 4.3.0 is (4, 0x30).
+    pub version: (u8, u8),
+    pub device_class: Signature,
+    pub color_space: Signature,
+    pub connection_space: Signature,
+    /// The creation date and time as (year, month, day, hours, minutes, seconds), in UTC.
+    pub created: [u16; 6],
+    pub rendering_intent: u32,
+    /// The illuminant of the profile connection space, which is always D50 in practice.
+    pub illuminant: Vector3<f64>,
+    pub creator: Signature,
+    /// The MD5 checksum of the profile, or all zeros if it was not computed.
+    pub profile_id: [u8; 16],
+}
+
+/// An entry in the tag table of an ICC profile.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TagEntry {
+    signature: Signature,
+    offset: usize,
+    size: usize,
+}
+
+/// A parsed ICC profile.
+///
+/// Only the header and tag table are parsed up front. Individual tags are decoded on demand.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IccProfile {
+    pub header: IccHeader,
+    tags: Vec<TagEntry>,
+    data: Vec<u8>,
+}
+
+impl IccProfile {
+    /// Parse an ICC version 2 or 4 profile.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        if data.len() < HEADER_SIZE + 4 {
+            return Err(IccParseError(format!(
+                "A profile is at least {} bytes, got {}",
+                HEADER_SIZE + 4,
+                data.len()
+            )));
+        }
+        if &data[36..40] != b"acsp" {
+            return Err(IccParseError(String::from(
+                "Missing the 'acsp' profile file signature",
+            )));
+        }
+        let size = read_u32(data, 0)?;
+        if (size as usize) < HEADER_SIZE + 4 {
+            return Err(IccParseError(format!(
+                "The header declares {} bytes, but a profile is at least {}",
+                size,
+                HEADER_SIZE + 4
+            )));
+        }
+        if size as usize > data.len() {
+            return Err(IccParseError(format!(
+                "The header declares {} bytes, but only {} are present",
+                size,
+                data.len()
+            )));
+        }
+        let data = &data[..size as usize];
+        let header = IccHeader {
+            size,
+            preferred_cmm: read_signature(data, 4)?,
+            version: {
+                let version = read_bytes(data, 8, 2)?;
+                (version[0], version[1])
+            },
+            device_class: read_signature(data, 12)?,
+            color_space: read_signature(data, 16)?,
+            connection_space: read_signature(data, 20)?,
+            created: {
+                let mut created = [0; 6];
+                for (i, value) in created.iter_mut().enumerate() {
+                    *value = read_u16(data, 24 + 2 * i)?;
+                }
+                created
+            },
+            rendering_intent: read_u32(data, 64)?,
+            illuminant: read_xyz_number(data, 68)?,
+            creator: read_signature(data, 80)?,
+            profile_id: {
+                let mut id = [0; 16];
+                id.copy_from_slice(read_bytes(data, 84, 16)?);
+                id
+            },
+        };
+
+        let count = read_u32(data, HEADER_SIZE)? as usize;
+        let mut tags = Vec::with_capacity(count.min(data.len() / 12));
+        for i in 0..count {
+            let position = HEADER_SIZE + 4 + 12 * i;
+            let tag = TagEntry {
+                signature: read_signature(data, position)?,
+                offset: read_u32(data, position + 4)? as usize,
+                size: read_u32(data, position + 8)? as usize,
+            };
+            if tag
+                .offset
+                .checked_add(tag.size)
+                .map_or(true, |end| end > data.len())
+            {
+                return Err(IccParseError(format!(
+                    "Tag {} extends past the end of the profile",
+                    signature_string(&tag.signature)
+                )));
+            }
+            tags.push(tag);
+        }
+
+        Ok(IccProfile {
+            header,
+            tags,
+            data: data.to_vec(),
+        })
+    }
+
+    /// Read and parse an ICC profile from a file.
+    pub fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let data = fs::read(path.as_ref()).map_err(|e| {
+            IccParseError(format!("Could not read {}: {}", path.as_ref().display(), e))
+        })?;
+        Self::parse(&data)
+    }
+
+    /// The signatures of all tags in the profile, in the order of the tag table.
+    pub fn tag_signatures(&self) -> Vec<Signature> {
+        self.tags.iter().map(|tag| tag.signature).collect()
+    }
+
+    /// The raw data of a tag, including its type signature.
+    pub fn tag_data(&self, signature: &Signature) -> Option<&[u8]> {
+        self.tags
+            .iter()
+            .find(|tag| &tag.signature == signature)
+            .map(|tag| &self.data[tag.offset..tag.offset + tag.size])
+    }
+
+    /// Decode a tag of XYZType containing a single XYZ number, such as a colorant or white point.
+    pub fn read_xyz(&self, signature: &Signature) -> Result<Vector3<f64>, Error> {
+        let data = self.required_tag(signature, b"XYZ ")?;
+        read_xyz_number(data, 8)
+    }
+
+    /// Decode a tag of s15Fixed16ArrayType containing a 3x3 matrix in row-major order, such as
+    /// the chromatic adaptation tag.
+    pub fn read_matrix(&self, signature: &Signature) -> Result<Matrix3<f64>, Error> {
+        let data = self.required_tag(signature, b"sf32")?;
+        let mut values = [0.0; 9];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = read_s15_fixed16(data, 8 + 4 * i)?;
+        }
+        Ok(Matrix3::from_row_slice(&values))
+    }
+
+    /// Decode a tone response curve tag of curveType or parametricCurveType.
+    pub fn read_curve(&self, signature: &Signature) -> Result<Arc<dyn ToneResponseCurve>, Error> {
+        let data = self
+            .tag_data(signature)
+            .ok_or_else(|| missing_tag(signature))?;
+        parse_curve(data)
+            .map(|(curve, _)| curve)
+            .map_err(|e| match e {
+                IccParseError(message) => {
+                    IccParseError(format!("Tag {}: {}", signature_string(signature), message))
+                }
+                e => e,
+            })
+    }
+
+    /// Decode a text tag of multiLocalizedUnicodeType, returning its first record, or of the
+    /// version 2 textDescriptionType or textType.
+    pub fn read_text(&self, signature: &Signature) -> Result<String, Error> {
+        let data = self
+            .tag_data(signature)
+            .ok_or_else(|| missing_tag(signature))?;
+        match &read_signature(data, 0)? {
+            b"mluc" => {
+                if read_u32(data, 8)? == 0 {
+                    return Ok(String::new());
+                }
+                let length = read_u32(data, 20)? as usize;
+                let offset = read_u32(data, 24)? as usize;
+                let units: Vec<u16> = read_bytes(data, offset, length)?
+                    .chunks(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+                    .collect();
+                String::from_utf16(&units).map_err(|_| {
+                    IccParseError(format!(
+                        "Tag {} is not valid UTF-16",
+                        signature_string(signature)
+                    ))
+                })
+            }
+            b"desc" => {
+                let length = read_u32(data, 8)? as usize;
+                Ok(ascii_string(read_bytes(data, 12, length)?))
+            }
+            b"text" => Ok(ascii_string(data.get(8..).unwrap_or(&[]))),
+            other => Err(IccParseError(format!(
+                "Tag {} has unexpected type {}",
+                signature_string(signature),
+                signature_string(other)
+            ))),
+        }
+    }
+
+    /// Compute the profile ID, the MD5 checksum of the profile with the flags, rendering intent,
+    /// and profile ID header fields set to zero.
+    ///
+    /// For a profile with a valid ID, this equals `header.profile_id`.
+    pub fn compute_profile_id(&self) -> [u8; 16] {
+        profile_id(&self.data)
+    }
+
+    /// The media white point of the profile.
+    pub fn white_point(&self) -> Result<Vector3<f64>, Error> {
+        self.read_xyz(b"wtpt")
+    }
+
+    /// The matrix adapting colors from the white point of the device to the illuminant of the
+    /// profile connection space.
+    ///
+    /// Version 4 profiles must record it in the chromatic adaptation tag. Version 2 profiles
+    /// usually omit it and record the actual white point instead, in which case it is computed
+    /// with the Bradford transform, as most color management modules do.
+    pub fn chromatic_adaptation(&self) -> Result<Matrix3<f64>, Error> {
+        if self.tag_data(b"chad").is_some() {
+            return self.read_matrix(b"chad");
+        }
+        match self.white_point() {
+            Ok(white) => Ok(bradford_adaptation(white, self.header.illuminant)),
+            Err(_) => Ok(Matrix3::identity()),
+        }
+    }
+
+    /// Build the transform of a matrix/TRC RGB profile, such as a typical display profile.
+    pub fn matrix_trc_transform(&self) -> Result<MatrixTRCTransform, Error> {
+        if self.header.color_space != RGB_SPACE || self.header.connection_space != XYZ_SPACE {
+            return Err(IccParseError(format!(
+                "Expected an RGB profile with an XYZ connection space, got {} and {}",
+                signature_string(&self.header.color_space),
+                signature_string(&self.header.connection_space)
+            )));
+        }
+        let matrix = Matrix3::from_columns(&[
+            self.read_xyz(b"rXYZ")?,
+            self.read_xyz(b"gXYZ")?,
+            self.read_xyz(b"bXYZ")?,
+        ]);
+        MatrixTRCTransform::new(
+            matrix,
+            self.read_curve(b"rTRC")?,
+            self.read_curve(b"gTRC")?,
+            self.read_curve(b"bTRC")?,
+            self.chromatic_adaptation()?,
+        )
+        .ok_or_else(|| {
+            IccParseError(String::from(
+                "The colorant and chromatic adaptation matrices must be invertible",
+            ))
+        })
+    }
+
+    /// Build the transform of a LUT-based profile for a rendering intent, such as that of a
+    /// CMYK printer. The AToB and BToA tags of the intent are used, falling back to AToB0 and
+    /// BToA0 as the ICC specification requires. The absolute colorimetric intent uses the
+    /// relative colorimetric tags, since scaling by the media white point is up to the caller.
+    ///
+    /// The chromatic adaptation tag is used if present. Otherwise, the profile connection space
+    /// is assumed to be adapted from D65 with the Bradford transform, rather than from the media
+    /// white point, which for a printer is the color of the paper.
+    pub fn lut_transform(&self, intent: RenderingIntent) -> Result<LutTransform, Error> {
+        let connection_space = self.header.connection_space;
+        if connection_space != XYZ_SPACE && connection_space != LAB_SPACE {
+            return Err(IccParseError(format!(
+                "Unknown profile connection space {}",
+                signature_string(&connection_space)
+            )));
+        }
+        let xyz = connection_space == XYZ_SPACE;
+        let number = match intent {
+            RenderingIntent::AbsoluteColorimetric => b'1',
+            intent => b'0' + intent.number(),
+        };
+        let tag = |prefix: &[u8; 3]| {
+            let signature = [prefix[0], prefix[1], prefix[2], number];
+            self.tag_data(&signature)
+                .or_else(|| self.tag_data(&[prefix[0], prefix[1], prefix[2], b'0']))
+        };
+        let to_pcs = Lut::parse(tag(b"A2B").ok_or_else(|| missing_tag(b"A2B0"))?, false)?;
+        let from_pcs = match tag(b"B2A") {
+            Some(data) => Some(Lut::parse(data, xyz)?),
+            None => None,
+        };
+        if to_pcs.output_channels != 3
+            || from_pcs.as_ref().is_some_and(|lut| lut.input_channels != 3)
+        {
+            return Err(IccParseError(String::from(
+                "The profile connection space has 3 channels",
+            )));
+        }
+        let chromatic_adaptation_matrix = if self.tag_data(b"chad").is_some() {
+            self.read_matrix(b"chad")?
+        } else {
+            bradford_adaptation(get_d65_ciexyz().to_vector3(), self.header.illuminant)
+        };
+        let chromatic_adaptation_inverse =
+            chromatic_adaptation_matrix.try_inverse().ok_or_else(|| {
+                IccParseError(String::from(
+                    "The chromatic adaptation matrix is not invertible",
+                ))
+            })?;
+        Ok(LutTransform {
+            to_pcs,
+            from_pcs,
+            connection_space,
+            chromatic_adaptation_matrix,
+            chromatic_adaptation_inverse,
+            interpolation: Interpolation::Tetrahedral,
+        })
+    }
+
+    /// Read a matrix/TRC display profile, such as one written by `DisplayProfile::to_bytes`.
+    pub fn display_profile(&self) -> Result<DisplayProfile, Error> {
+        Ok(DisplayProfile {
+            description: self.read_text(b"desc")?,
+            copyright: match self.tag_data(b"cprt") {
+                Some(_) => self.read_text(b"cprt")?,
+                None => String::new(),
+            },
+            created: self.header.created,
+            transform: self.matrix_trc_transform()?,
+        })
+    }
+
+    fn required_tag(&self, signature: &Signature, tag_type: &Signature) -> Result<&[u8], Error> {
+        let data = self
+            .tag_data(signature)
+            .ok_or_else(|| missing_tag(signature))?;
+        let actual = read_signature(data, 0)?;
+        if &actual != tag_type {
+            return Err(IccParseError(format!(
+                "Tag {} has type {}, expected {}",
+                signature_string(signature),
+                signature_string(&actual),
+                signature_string(tag_type)
+            )));
+        }
+        Ok(data)
+    }
+}
+
+/// The linear Bradford chromatic adaptation transform between two white points.
+pub(super) fn bradford_adaptation(source: Vector3<f64>, destination: Vector3<f64>) -> Matrix3<f64> {
+    #[rustfmt::skip]
+    let bradford = Matrix3::new(
+        0.8951, 0.2664, -0.1614,
+        -0.7502, 1.7135, 0.0367,
+        0.0389, -0.0685, 1.0296,
+    );
+    let inverse = bradford
+        .try_inverse()
+        .expect("The Bradford matrix is invertible.");
+    let scale = (bradford * destination).component_div(&(bradford * source));
+    inverse * Matrix3::from_diagonal(&scale) * bradford
+}
+
+/// Decode a curveType or parametricCurveType, returning the curve and the length of its
+/// encoding in bytes, excluding padding.
+pub(super) fn parse_curve(data: &[u8]) -> Result<(Arc<dyn ToneResponseCurve>, usize), Error> {
+    match &read_signature(data, 0)? {
+        b"curv" => {
+            let count = read_u32(data, 8)? as usize;
+            let length = 12 + 2 * count;
+            match count {
+                0 => Ok((Arc::new(ParametricCurveType0 { g: 1.0 }), length)),
+                1 => Ok((
+                    Arc::new(ParametricCurveType0 {
+                        g: f64::from(read_u16(data, 12)?) / 256.0,
+                    }),
+                    length,
+                )),
+                _ => {
+                    let samples = (0..count)
+                        .map(|i| Ok(f64::from(read_u16(data, 12 + 2 * i)?) / 65535.0))
+                        .collect::<Result<Vec<f64>, Error>>()?;
+                    let curve =
+                        SampledCurve::new(samples).expect("The curve has at least two samples.");
+                    Ok((Arc::new(curve), length))
+                }
+            }
+        }
+        b"para" => {
+            let function_type = read_u16(data, 8)?;
+            let parameter = |i: usize| read_s15_fixed16(data, 12 + 4 * i);
+            let curve: Arc<dyn ToneResponseCurve> = match function_type {
+                0 => Arc::new(ParametricCurveType0 { g: parameter(0)? }),
+                1 => Arc::new(ParametricCurveType1 {
+                    g: parameter(0)?,
+                    a: parameter(1)?,
+                    b: parameter(2)?,
+                }),
+                2 => Arc::new(ParametricCurveType2 {
+                    g: parameter(0)?,
+                    a: parameter(1)?,
+                    b: parameter(2)?,
+                    c: parameter(3)?,
+                }),
+                3 => Arc::new(ParametricCurveType3 {
+                    g: parameter(0)?,
+                    a: parameter(1)?,
+                    b: parameter(2)?,
+                    c: parameter(3)?,
+                    d: parameter(4)?,
+                }),
+                4 => Arc::new(ParametricCurveType4 {
+                    g: parameter(0)?,
+                    a: parameter(1)?,
+                    b: parameter(2)?,
+                    c: parameter(3)?,
+                    d: parameter(4)?,
+                    e: parameter(5)?,
+                    f: parameter(6)?,
+                }),
+                _ => {
+                    return Err(IccParseError(format!(
+                        "Unknown parametric curve type {}",
+                        function_type
+                    )))
+                }
+            };
+            let parameters = [1, 3, 4, 5, 7][usize::from(function_type)];
+            Ok((curve, 12 + 4 * parameters))
+        }
+        other => Err(IccParseError(format!(
+            "Expected a curve, got type {}",
+            signature_string(other)
+        ))),
+    }
+}
+
+/// Compute the ID of serialized profile data.
+pub(super) fn profile_id(data: &[u8]) -> [u8; 16] {
+    let mut data = data.to_vec();
+    for range in [44..48, 64..68, 84..100].iter() {
+        for byte in &mut data[range.clone()] {
+            *byte = 0;
+        }
+    }
+    md5(&data)
+}
+
+fn ascii_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+pub(super) fn missing_tag(signature: &Signature) -> Error {
+    IccParseError(format!(
+        "Missing required tag {}",
+        signature_string(signature)
+    ))
+}
+
+pub(super) fn signature_string(signature: &Signature) -> String {
+    String::from_utf8_lossy(signature).into_owned()
+}
+
+pub(super) fn read_bytes(data: &[u8], offset: usize, length: usize) -> Result<&[u8], Error> {
+    let end = offset.checked_add(length);
+    end.and_then(|end| data.get(offset..end)).ok_or_else(|| {
+        IccParseError(format!(
+            "Unexpected end of data reading {} bytes at offset {}",
+            length, offset
+        ))
+    })
+}
+
+pub(super) fn read_signature(data: &[u8], offset: usize) -> Result<Signature, Error> {
+    let mut signature = [0; 4];
+    signature.copy_from_slice(read_bytes(data, offset, 4)?);
+    Ok(signature)
+}
+
+pub(super) fn read_u16(data: &[u8], offset: usize) -> Result<u16, Error> {
+    let bytes = read_bytes(data, offset, 2)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+pub(super) fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    Ok(u32::from_be_bytes(read_signature(data, offset)?))
+}
+
+pub(super) fn read_s15_fixed16(data: &[u8], offset: usize) -> Result<f64, Error> {
+    Ok(f64::from(read_u32(data, offset)? as i32) / 65536.0)
+}
+
+pub(super) fn read_xyz_number(data: &[u8], offset: usize) -> Result<Vector3<f64>, Error> {
+    Ok(Vector3::new(
+        read_s15_fixed16(data, offset)?,
+        read_s15_fixed16(data, offset + 4)?,
+        read_s15_fixed16(data, offset + 8)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{Color, CIEXYZ, SRGB};
+
+    /// Assemble a profile from a header and (signature, data) tags.
+    fn build_profile(version: u8, tags: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let table_size = 4 + 12 * tags.len();
+        let mut offset = HEADER_SIZE + table_size;
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut body = Vec::new();
+        for (signature, data) in tags {
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&(offset as u32).to_be_bytes());
+            table.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let padded = data.len().div_ceil(4) * 4;
+            body.extend_from_slice(data);
+            body.resize(body.len() + padded - data.len(), 0);
+            offset += padded;
+        }
+        let mut header = vec![0; HEADER_SIZE];
+        header[0..4].copy_from_slice(&(offset as u32).to_be_bytes());
+        header[8] = version;
+        header[12..16].copy_from_slice(&DISPLAY_CLASS);
+        header[16..20].copy_from_slice(&RGB_SPACE);
+        header[20..24].copy_from_slice(&XYZ_SPACE);
+        header[36..40].copy_from_slice(b"acsp");
+        header[68..80].copy_from_slice(&xyz_number([0.9642, 1.0, 0.8249]));
+        [header, table, body].concat()
+    }
+
+    fn fixed(value: f64) -> [u8; 4] {
+        ((value * 65536.0).round() as i32).to_be_bytes()
+    }
+
+    fn xyz_number(values: [f64; 3]) -> Vec<u8> {
+        values.iter().flat_map(|v| fixed(*v).to_vec()).collect()
+    }
+
+    fn xyz_tag(values: [f64; 3]) -> Vec<u8> {
+        [b"XYZ \0\0\0\0".to_vec(), xyz_number(values)].concat()
+    }
+
+    fn srgb_profile() -> Vec<u8> {
+        let parameters = [157_286.0, 62119.0, 3417.0, 5072.0, 2651.0];
+        let mut trc = b"para\0\0\0\0\0\x03\0\0".to_vec();
+        for p in parameters.iter() {
+            trc.extend_from_slice(&(*p as i32).to_be_bytes());
+        }
+        let chad = [
+            68674.0, 1502.0, -3290.0, 1939.0, 64912.0, -1118.0, -605.0, 988.0, 49262.0,
+        ];
+        let mut chad_tag = b"sf32\0\0\0\0".to_vec();
+        for value in chad.iter() {
+            chad_tag.extend_from_slice(&(*value as i32).to_be_bytes());
+        }
+        build_profile(
+            4,
+            &[
+                (b"wtpt", xyz_tag([0.9642, 1.0, 0.8249])),
+                (
+                    b"rXYZ",
+                    xyz_tag([28578.0 / 65536.0, 14581.0 / 65536.0, 912.0 / 65536.0]),
+                ),
+                (
+                    b"gXYZ",
+                    xyz_tag([25241.0 / 65536.0, 46981.0 / 65536.0, 6362.0 / 65536.0]),
+                ),
+                (
+                    b"bXYZ",
+                    xyz_tag([9376.0 / 65536.0, 3972.0 / 65536.0, 46799.0 / 65536.0]),
+                ),
+                (b"rTRC", trc.clone()),
+                (b"gTRC", trc.clone()),
+                (b"bTRC", trc),
+                (b"chad", chad_tag),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_parse_header() -> Result<(), Error> {
+        let profile = IccProfile::parse(&srgb_profile())?;
+        assert_eq!(profile.header.version, (4, 0));
+        assert_eq!(profile.header.device_class, DISPLAY_CLASS);
+        assert_eq!(profile.header.color_space, RGB_SPACE);
+        assert!((profile.header.illuminant - Vector3::new(0.9642, 1.0, 0.8249)).amax() < 1e-4);
+        assert_eq!(profile.tag_signatures().len(), 8);
+        Ok(())
+    }
+
+    #[test]
+    fn test_matrix_trc_transform_matches_srgb() -> Result<(), Error> {
+        let transform = IccProfile::parse(&srgb_profile())?.matrix_trc_transform()?;
+        for hex in &["ff0000", "00ff00", "0000ff", "808080", "ffffff", "123456"] {
+            let srgb = SRGB::from_hex(hex)?;
+            let expected = srgb.to_ciexyz().to_vector3();
+            let actual: CIEXYZ = transform.to_color(srgb.to_vector3());
+            assert!((actual.to_vector3() - expected).amax() < 1e-9);
+            assert!((transform.from_color(&srgb) - srgb.to_vector3()).amax() < 1e-9);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_2_white_point_adaptation() -> Result<(), Error> {
+        // A version 2 profile without a chromatic adaptation tag records the D65 white point.
+        let gamma_curve = b"curv\0\0\0\0\0\0\0\x01\x02\x33".to_vec();
+        let data = build_profile(
+            2,
+            &[
+                (b"wtpt", xyz_tag([0.95047, 1.0, 1.08883])),
+                (b"rXYZ", xyz_tag([0.4361, 0.2225, 0.0139])),
+                (b"gXYZ", xyz_tag([0.3851, 0.7169, 0.0971])),
+                (b"bXYZ", xyz_tag([0.1431, 0.0606, 0.7141])),
+                (b"rTRC", gamma_curve.clone()),
+                (b"gTRC", gamma_curve.clone()),
+                (b"bTRC", gamma_curve),
+            ],
+        );
+        let transform = IccProfile::parse(&data)?.matrix_trc_transform()?;
+        assert!((transform.red_trc().apply(0.5) - 0.5_f64.powf(2.19921875)).abs() < 1e-12);
+        let white = transform.to_profile_connection_space(Vector3::new(1.0, 1.0, 1.0));
+        assert!((white - Vector3::new(0.95047, 1.0, 1.08883)).amax() < 2e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tabulated_and_type_4_curves() -> Result<(), Error> {
+        let mut table = b"curv\0\0\0\0\0\0\x01\0".to_vec();
+        for i in 0..256 {
+            let value = (f64::from(i) / 255.0).powf(1.8) * 65535.0;
+            table.extend_from_slice(&(value.round() as u16).to_be_bytes());
+        }
+        let mut type4 = b"para\0\0\0\0\0\x04\0\0".to_vec();
+        for p in [
+            2.4,
+            1.0 / 1.055,
+            0.055 / 1.055,
+            1.0 / 12.92,
+            0.04045,
+            0.0,
+            0.0,
+        ]
+        .iter()
+        {
+            type4.extend_from_slice(&fixed(*p));
+        }
+        let data = build_profile(
+            4,
+            &[
+                (b"rXYZ", xyz_tag([0.4361, 0.2225, 0.0139])),
+                (b"gXYZ", xyz_tag([0.3851, 0.7169, 0.0971])),
+                (b"bXYZ", xyz_tag([0.1431, 0.0606, 0.7141])),
+                (b"rTRC", table.clone()),
+                (b"gTRC", table),
+                (b"bTRC", type4),
+            ],
+        );
+        let transform = IccProfile::parse(&data)?.matrix_trc_transform()?;
+        assert!((transform.red_trc().apply(0.5) - 0.5_f64.powf(1.8)).abs() < 1e-4);
+        assert!((transform.blue_trc().apply(0.5) - 0.214).abs() < 1e-3);
+        let device = Vector3::new(0.2, 0.5, 0.8);
+        let round_trip = transform.to_device_space(transform.to_profile_connection_space(device));
+        assert!((round_trip - device).amax() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_profiles() {
+        assert!(IccProfile::parse(&[0; 64]).is_err());
+        let mut data = srgb_profile();
+        data[36] = b'x';
+        assert!(IccProfile::parse(&data).is_err());
+
+        for size in [8_u32, 9, 84, 99, HEADER_SIZE as u32 + 3].iter() {
+            let mut data = srgb_profile();
+            data[0..4].copy_from_slice(&size.to_be_bytes());
+            assert!(IccProfile::parse(&data).is_err());
+        }
+
+        let mut data = srgb_profile();
+        // Point the first tag past the end of the profile.
+        data[HEADER_SIZE + 8..HEADER_SIZE + 12].copy_from_slice(&0xffff_u32.to_be_bytes());
+        assert!(IccProfile::parse(&data).is_err());
+
+        let data = build_profile(2, &[(b"cprt", b"text\0\0".to_vec())]);
+        let profile = IccProfile::parse(&data).expect("The profile is well formed.");
+        assert_eq!(profile.read_text(b"cprt"), Ok(String::new()));
+
+        let data = build_profile(4, &[(b"rXYZ", xyz_tag([0.4, 0.2, 0.0]))]);
+        let profile = IccProfile::parse(&data).expect("The profile is well formed.");
+        assert!(profile.matrix_trc_transform().is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use nalgebra::Vector3;
+
+use super::{IccProfile, LutTransform, D50, RGB_SPACE, XYZ_SPACE};
+use crate::colors::MatrixTRCTransform;
+use crate::errors::Error;
+
+/// The rendering intent of a transform between profiles, which determines how colors are mapped
+/// when the gamuts or white points of the profiles differ.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderingIntent {
+    /// Compress the whole source gamut into the destination gamut, preserving the relationships
+    /// between colors. Suited to photographs.
+    Perceptual,
+    /// Reproduce in-gamut colors exactly relative to the white of each medium, and clip the rest.
+    RelativeColorimetric,
+    /// Preserve the saturation of colors at the expense of accuracy. Suited to business graphics.
+    Saturation,
+    /// Reproduce in-gamut colors exactly, including the color of the medium, such as when
+    /// proofing one printing press on another.
+    AbsoluteColorimetric,
+}
+
+impl RenderingIntent {
+    /// The number of the intent in ICC headers and tag signatures such as A2B0.
+    pub fn number(self) -> u8 {
+        match self {
+            RenderingIntent::Perceptual => 0,
+            RenderingIntent::RelativeColorimetric => 1,
+            RenderingIntent::Saturation => 2,
+            RenderingIntent::AbsoluteColorimetric => 3,
+        }
+    }
+}
+
+/// The mapping between the device space of a profile and the D50 profile connection space, for a
+/// particular rendering intent.
+#[derive(Clone, Debug)]
+pub enum DeviceTransform {
+    MatrixTrc(Box<MatrixTRCTransform>),
+    Lut(Box<LutTransform>),
+}
+
+impl DeviceTransform {
+    /// Build the transform of a profile for a rendering intent. Matrix/TRC profiles are
+    /// colorimetric, so they are the same for all intents.
+    pub fn from_profile(profile: &IccProfile, intent: RenderingIntent) -> Result<Self, Error> {
+        let header = &profile.header;
+        if header.color_space == RGB_SPACE
+            && header.connection_space == XYZ_SPACE
+            && profile.tag_data(b"A2B0").is_none()
+        {
+            Ok(DeviceTransform::MatrixTrc(Box::new(
+                profile.matrix_trc_transform()?,
+            )))
+        } else {
+            Ok(DeviceTransform::Lut(Box::new(
+                profile.lut_transform(intent)?,
+            )))
+        }
+    }
+
+    /// Transform from device space to D50 PCS XYZ, or `None` if the number of device values is
+    /// not the number of channels.
+    pub fn to_pcs(&self, device: &[f64]) -> Option<Vector3<f64>> {
+        if device.len() != self.channels() {
+            return None;
+        }
+        Some(match self {
+            DeviceTransform::MatrixTrc(transform) => {
+                transform.matrix()
+                    * transform.apply_trc(Vector3::new(device[0], device[1], device[2]))
+            }
+            DeviceTransform::Lut(transform) => transform.to_profile_connection_space(device),
+        })
+    }
+
+    /// Transform from D50 PCS XYZ to device space, or `None` if the profile cannot be used as a
+    /// destination.
+    pub fn from_pcs(&self, xyz: Vector3<f64>) -> Option<Vec<f64>> {
+        match self {
+            DeviceTransform::MatrixTrc(transform) => {
+                let linear = transform.matrix_inverse() * xyz;
+                Some(transform.apply_trc_inverse(linear).as_slice().to_vec())
+            }
+            DeviceTransform::Lut(transform) => transform.to_device_space(xyz),
+        }
+    }
+
+    /// The number of device channels.
+    pub fn channels(&self) -> usize {
+        match self {
+            DeviceTransform::MatrixTrc(_) => 3,
+            DeviceTransform::Lut(transform) => transform.to_pcs.input_channels,
+        }
+    }
+
+    /// Estimate the darkest color the device can reproduce, in D50 PCS XYZ, made neutral.
+    ///
+    /// This is the PCS black round-tripped through the device if possible, so that it accounts
+    /// for ink limits. Otherwise it is the color of the device values that are darkest for a
+    /// typical device: zeros for RGB and gray, and full coverage for CMYK and other inks.
+    pub fn black_point(&self) -> Vector3<f64> {
+        let channels = self.channels();
+        let darkest = if channels >= 4 { 1.0 } else { 0.0 };
+        let black = self
+            .from_pcs(Vector3::zeros())
+            .and_then(|device| self.to_pcs(&device))
+            .or_else(|| self.to_pcs(&vec![darkest; channels]))
+            .expect("There is one device value per channel.");
+        Vector3::from(D50) * black[1].max(0.0)
+    }
+}
+
+/// A transform between the device spaces of two profiles through the profile connection space.
+#[derive(Clone, Debug)]
+pub struct IccTransform {
+    source: DeviceTransform,
+    destination: DeviceTransform,
+    /// The factors by which to multiply relative PCS XYZ of the source to reach the destination,
+    /// which are not 1 only for the absolute colorimetric intent.
+    white_scale: Vector3<f64>,
+    /// The scale and offset of the black point compensation applied to PCS XYZ, if enabled.
+    black_point_compensation: Option<(Vector3<f64>, Vector3<f64>)>,
+}
+
+impl IccTransform {
+    /// Build a transform from the device space of one profile to another.
+    ///
+    /// Black point compensation maps the black point of the source to the black point of the
+    /// destination, scaling everything in between, so that shadow detail is not clipped. It is
+    /// ignored for the absolute colorimetric intent.
+    pub fn new(
+        source: &IccProfile,
+        destination: &IccProfile,
+        intent: RenderingIntent,
+        black_point_compensation: bool,
+    ) -> Result<Self, Error> {
+        let media_white = |profile: &IccProfile| {
+            if profile.tag_data(b"wtpt").is_some() {
+                profile.white_point()
+            } else {
+                Ok(Vector3::from(D50))
+            }
+        };
+        Ok(Self::from_device_transforms(
+            DeviceTransform::from_profile(source, intent)?,
+            media_white(source)?,
+            DeviceTransform::from_profile(destination, intent)?,
+            media_white(destination)?,
+            intent,
+            black_point_compensation,
+        ))
+    }
+
+    /// Build a transform from device transforms, such as the built-in `SRGB::matrix_trc_transform`,
+    /// and the media white points of their profiles, which are only used for the absolute
+    /// colorimetric intent. The media white point of a display is D50.
+    pub fn from_device_transforms(
+        source: DeviceTransform,
+        source_white: Vector3<f64>,
+        destination: DeviceTransform,
+        destination_white: Vector3<f64>,
+        intent: RenderingIntent,
+        black_point_compensation: bool,
+    ) -> Self {
+        let absolute = intent == RenderingIntent::AbsoluteColorimetric;
+        let white_scale = if absolute {
+            source_white.component_div(&destination_white)
+        } else {
+            Vector3::repeat(1.0)
+        };
+        let black_point_compensation = if black_point_compensation && !absolute {
+            // Adobe, "Adobe Black Point Compensation" (2006), applied in XYZ as in Little CMS.
+            let white = Vector3::from(D50);
+            let (source_black, destination_black) =
+                (source.black_point(), destination.black_point());
+            let scale = (white - destination_black).component_div(&(white - source_black));
+            let offset = white
+                .component_mul(&(destination_black - source_black))
+                .component_div(&(white - source_black));
+            Some((scale, offset))
+        } else {
+            None
+        };
+        IccTransform {
+            source,
+            destination,
+            white_scale,
+            black_point_compensation,
+        }
+    }
+
+    /// Transform device values of the source profile to the destination profile, or `None` if
+    /// the number of values is not the number of source channels, or the destination profile
+    /// cannot be used as a destination.
+    pub fn apply(&self, input: &[f64]) -> Option<Vec<f64>> {
+        let mut xyz = self.source.to_pcs(input)?.component_mul(&self.white_scale);
+        if let Some((scale, offset)) = &self.black_point_compensation {
+            xyz = xyz.component_mul(scale) + offset;
+        }
+        self.destination.from_pcs(xyz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::colors::{Color, DisplayP3, RGBColorSpace, SampledCurve, SRGB};
+
+    fn srgb() -> DeviceTransform {
+        DeviceTransform::MatrixTrc(Box::new(SRGB::matrix_trc_transform().clone()))
+    }
+
+    fn display_p3() -> DeviceTransform {
+        DeviceTransform::MatrixTrc(Box::new(DisplayP3::matrix_trc_transform().clone()))
+    }
+
+    fn d50() -> Vector3<f64> {
+        Vector3::from(D50)
+    }
+
+    #[test]
+    fn test_srgb_to_display_p3() -> Result<(), Error> {
+        let transform = IccTransform::from_device_transforms(
+            srgb(),
+            d50(),
+            display_p3(),
+            d50(),
+            RenderingIntent::RelativeColorimetric,
+            false,
+        );
+        for hex in &["ff0000", "00ff00", "0000ff", "808080", "ffffff", "123456"] {
+            let color = SRGB::from_hex(hex)?;
+            let expected = color.to_color::<DisplayP3>().to_vector3();
+            let actual = transform
+                .apply(color.to_vector3().as_slice())
+                .expect("Display P3 is a valid destination.");
+            assert!((Vector3::from_vec(actual) - expected).amax() < 1e-9);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrong_number_of_channels() {
+        let transform = IccTransform::from_device_transforms(
+            srgb(),
+            d50(),
+            display_p3(),
+            d50(),
+            RenderingIntent::RelativeColorimetric,
+            false,
+        );
+        assert!(srgb().to_pcs(&[1.0, 0.0]).is_none());
+        assert!(transform.apply(&[1.0, 0.0]).is_none());
+        assert!(transform.apply(&[1.0, 0.0, 0.0, 0.0]).is_none());
+        assert!(transform.apply(&[1.0, 0.0, 0.0]).is_some());
+    }
+
+    #[test]
+    fn test_absolute_colorimetric_keeps_media_white() {
+        // A destination printed on yellowish paper.
+        let paper = Vector3::new(0.93, 0.97, 0.72);
+        let transform = |intent| {
+            IccTransform::from_device_transforms(srgb(), d50(), srgb(), paper, intent, false)
+        };
+        let white = [1.0, 1.0, 1.0];
+        let relative = transform(RenderingIntent::RelativeColorimetric)
+            .apply(&white)
+            .expect("sRGB is a valid destination.");
+        assert!(relative.iter().all(|v| (v - 1.0).abs() < 1e-9));
+        let absolute = transform(RenderingIntent::AbsoluteColorimetric)
+            .apply(&white)
+            .expect("sRGB is a valid destination.");
+        // Reproducing the white of the display on the paper requires more blue than it has.
+        assert!(absolute[2] > 1.0);
+    }
+
+    #[test]
+    fn test_black_point_compensation() {
+        // A display whose black is 5% of its white.
+        let curve = Arc::new(SampledCurve::new(vec![0.05, 1.0]).expect("There are two samples."));
+        let faded =
+            SRGB::matrix_trc_transform()
+                .clone()
+                .with_trcs(curve.clone(), curve.clone(), curve);
+        let faded = DeviceTransform::MatrixTrc(Box::new(faded));
+        assert!((faded.black_point()[1] - 0.05).abs() < 1e-4);
+
+        let transform = |black_point_compensation| {
+            IccTransform::from_device_transforms(
+                faded.clone(),
+                d50(),
+                srgb(),
+                d50(),
+                RenderingIntent::RelativeColorimetric,
+                black_point_compensation,
+            )
+        };
+        let black = [0.0, 0.0, 0.0];
+        let white = [1.0, 1.0, 1.0];
+        let without = transform(false)
+            .apply(&black)
+            .expect("sRGB is a destination.");
+        assert!(without.iter().all(|v| *v > 0.2));
+        let with = transform(true)
+            .apply(&black)
+            .expect("sRGB is a destination.");
+        assert!(with.iter().all(|v| v.abs() < 1e-3), "{:?}", with);
+        let with = transform(true)
+            .apply(&white)
+            .expect("sRGB is a destination.");
+        assert!(with.iter().all(|v| (v - 1.0).abs() < 1e-3), "{:?}", with);
+    }
+}
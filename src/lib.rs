@@ -1,5 +1,12 @@
 pub mod colors;
+pub mod cvd;
+pub mod difference;
 pub mod encodings;
 pub mod errors;
+pub mod gamut;
+pub mod icc;
 mod nalgebra_helpers;
+pub mod schemes;
+pub mod spectral;
+pub mod terminal;
 pub mod utils;
@@ -1,50 +1,47 @@
 /// Helper methods for nalgebra traits.
+///
+/// nalgebra 0.19 allocates matrix storage with `mem::uninitialized`, which aborts on current
+/// toolchains for element types such as `Option<T>` or `Result<T, E>`. These helpers therefore
+/// operate on plain arrays and only produce a `Vector3` once every element is a scalar.
 use nalgebra::{Scalar, Vector3};
 
-pub trait Vector3OptionExt<T>
+pub trait Array3OptionExt<T>
 where
     T: Scalar,
 {
     fn sequence_option(self) -> Option<Vector3<T>>;
 }
 
-impl<T> Vector3OptionExt<T> for Vector3<Option<T>>
+impl<T> Array3OptionExt<T> for [Option<T>; 3]
 where
     T: Scalar,
 {
     fn sequence_option(self) -> Option<Vector3<T>> {
-        let opt0 = self[0];
-        let opt1 = self[1];
-        let opt2 = self[2];
-        match (opt0, opt1, opt2) {
-            (Some(t0), Some(t1), Some(t2)) => Some(Vector3::new(t0, t1, t2)),
+        match self {
+            [Some(t0), Some(t1), Some(t2)] => Some(Vector3::new(t0, t1, t2)),
             _ => None,
         }
     }
 }
 
-pub trait Vector3ResultExt<T, E>
+#[allow(dead_code)]
+pub trait Array3ResultExt<T, E>
 where
     T: Scalar,
-    E: Scalar,
 {
     fn sequence_result(self) -> Result<Vector3<T>, E>;
 }
 
-impl<T, E> Vector3ResultExt<T, E> for Vector3<Result<T, E>>
+impl<T, E> Array3ResultExt<T, E> for [Result<T, E>; 3]
 where
     T: Scalar,
-    E: Scalar,
 {
     fn sequence_result(self) -> Result<Vector3<T>, E> {
-        let res0 = self[0];
-        let res1 = self[1];
-        let res2 = self[2];
-        match (res0, res1, res2) {
-            (Ok(t0), Ok(t1), Ok(t2)) => Ok(Vector3::new(t0, t1, t2)),
-            (Err(e), _, _) => Err(e),
-            (_, Err(e), _) => Err(e),
-            (_, _, Err(e)) => Err(e),
+        match self {
+            [Ok(t0), Ok(t1), Ok(t2)] => Ok(Vector3::new(t0, t1, t2)),
+            [Err(e), _, _] => Err(e),
+            [_, Err(e), _] => Err(e),
+            [_, _, Err(e)] => Err(e),
         }
     }
 }
@@ -56,29 +53,26 @@ mod tests {
     #[test]
     fn test_sequence_option_some() {
         assert_eq!(
-            Vector3::new(Some(1), Some(2), Some(3)).sequence_option(),
+            [Some(1), Some(2), Some(3)].sequence_option(),
             Some(Vector3::new(1, 2, 3))
         )
     }
 
     #[test]
     fn test_sequence_option_none() {
-        assert_eq!(Vector3::new(Some(1), None, Some(3)).sequence_option(), None)
+        assert_eq!([Some(1), None, Some(3)].sequence_option(), None)
     }
 
     #[test]
     fn test_sequence_result_ok() {
         assert_eq!(
-            Vector3::<Result<u8, ()>>::new(Ok(1), Ok(2), Ok(3)).sequence_result(),
+            [Ok::<u8, ()>(1), Ok(2), Ok(3)].sequence_result(),
             Ok(Vector3::new(1, 2, 3))
         )
     }
 
     #[test]
     fn test_sequence_result_err_multiple() {
-        assert_eq!(
-            Vector3::new(Ok(1), Err(2), Err(3)).sequence_result(),
-            Err(2)
-        )
+        assert_eq!([Ok(1), Err(2), Err(3)].sequence_result(), Err(2))
     }
 }
@@ -0,0 +1,250 @@
+use std::f64::consts::PI;
+
+use crate::colors::{Color, LchSpace, CIELAB, OKLAB};
+use crate::difference::DeltaE;
+use crate::gamut::Gamut;
+use crate::utils::contrast_ratio;
+
+/// The number of candidate samples drawn for every candidate that is kept, before giving up on
+/// constraints that are too narrow to satisfy.
+const MAX_ATTEMPTS_PER_CANDIDATE: usize = 20;
+
+/// The distance between two colors that a categorical palette maximizes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CategoricalDistance {
+    /// A CIELAB color difference formula.
+    DeltaE(DeltaE),
+    /// Euclidean distance in Oklab.
+    OKLab,
+}
+
+/// A color prepared for repeated distance computations.
+#[derive(Clone, Copy, Debug)]
+enum Point {
+    Lab(CIELAB),
+    OKLab(OKLAB),
+}
+
+impl CategoricalDistance {
+    fn point<C>(self, color: &C) -> Point
+    where
+        C: Color,
+    {
+        match self {
+            CategoricalDistance::DeltaE(_) => Point::Lab(color.to_color()),
+            CategoricalDistance::OKLab => Point::OKLab(color.to_color()),
+        }
+    }
+
+    fn distance(self, p1: &Point, p2: &Point) -> f64 {
+        match (self, p1, p2) {
+            (CategoricalDistance::DeltaE(metric), Point::Lab(lab1), Point::Lab(lab2)) => {
+                metric.compute(lab1, lab2)
+            }
+            (CategoricalDistance::OKLab, Point::OKLab(ok1), Point::OKLab(ok2)) => {
+                (ok1.to_vector3() - ok2.to_vector3()).magnitude()
+            }
+            _ => unreachable!("Points are always prepared by the same metric."),
+        }
+    }
+}
+
+/// Parameters of a palette of mutually distinct colors for categorical data.
+#[derive(Clone, Copy, Debug)]
+pub struct CategoricalPaletteParams<C> {
+    /// The number of colors to generate.
+    pub count: usize,
+    /// The background the colors are drawn on. Colors are kept distinct from it as well as from
+    /// each other.
+    pub background: C,
+    /// The minimum WCAG 2 contrast ratio of every color against the background.
+    pub min_contrast: f64,
+    /// The space in which the lightness and chroma ranges are specified.
+    pub space: LchSpace,
+    pub lightness_range: (f64, f64),
+    pub chroma_range: (f64, f64),
+    pub gamut: Gamut,
+    pub distance: CategoricalDistance,
+    /// The number of random candidates to choose colors from.
+    pub candidates: usize,
+    /// The seed of the random candidate generator. The same parameters always produce the same
+    /// palette.
+    pub seed: u64,
+}
+
+/// Generate a palette of colors that are as far apart from each other as possible.
+///
+/// Random candidates satisfying the lightness, chroma, gamut, and contrast constraints are drawn,
+/// and colors are then chosen greedily: each color is the candidate whose distance to the closest
+/// already chosen color (or the background) is largest. This is the approach of Glasbey et al.,
+/// "Colour Displays for Categorical Images" (2007).
+///
+/// Fewer than `count` colors are returned if not enough candidates satisfy the constraints.
+pub fn categorical_palette<C>(params: &CategoricalPaletteParams<C>) -> Vec<C>
+where
+    C: Color,
+{
+    let mut rng = SplitMix64(params.seed);
+    let mut candidates: Vec<C> = Vec::with_capacity(params.candidates);
+    for _ in 0..params.candidates * MAX_ATTEMPTS_PER_CANDIDATE {
+        if candidates.len() == params.candidates {
+            break;
+        }
+        let l = rng.next_in_range(params.lightness_range);
+        let c = rng.next_in_range(params.chroma_range);
+        let h = rng.next_in_range((0.0, 2.0 * PI));
+        let candidate: C = params.space.from_lch(l, c, h);
+        if params.gamut.contains(&candidate)
+            && contrast_ratio(candidate, params.background) >= params.min_contrast
+        {
+            candidates.push(candidate);
+        }
+    }
+
+    let distance = params.distance;
+    let points: Vec<Point> = candidates.iter().map(|c| distance.point(c)).collect();
+    let background = distance.point(&params.background);
+    let mut min_distances: Vec<f64> = points
+        .iter()
+        .map(|p| distance.distance(p, &background))
+        .collect();
+
+    let mut palette = Vec::with_capacity(params.count);
+    while palette.len() < params.count {
+        let best = min_distances
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.is_finite())
+            .max_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+            .map(|(i, _)| i);
+        let chosen = match best {
+            Some(i) => i,
+            None => break,
+        };
+        palette.push(candidates[chosen]);
+        min_distances[chosen] = f64::NEG_INFINITY;
+        for (i, point) in points.iter().enumerate() {
+            let d = distance.distance(point, &points[chosen]);
+            if d < min_distances[i] {
+                min_distances[i] = d;
+            }
+        }
+    }
+    palette
+}
+
+/// A small, fast pseudorandom number generator, so that palettes are reproducible without
+/// depending on an external crate.
+///
+/// See http://prng.di.unimi.it/splitmix64.c for the reference implementation.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed value in [low, high).
+    fn next_in_range(&mut self, (low, high): (f64, f64)) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        low + unit * (high - low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::*;
+    use crate::colors::SRGB;
+    use crate::errors::Error;
+
+    fn params(distance: CategoricalDistance) -> Result<CategoricalPaletteParams<SRGB>, Error> {
+        Ok(CategoricalPaletteParams {
+            count: 8,
+            background: SRGB::from_hex("ffffff")?,
+            min_contrast: 3.0,
+            space: LchSpace::OKLCH,
+            lightness_range: (0.3, 0.7),
+            chroma_range: (0.05, 0.3),
+            gamut: Gamut::SRGB,
+            distance,
+            candidates: 500,
+            seed: 1337,
+        })
+    }
+
+    fn min_pairwise_distance(colors: &[SRGB]) -> f64 {
+        let metric = DeltaE::CIEDE2000;
+        let labs: Vec<CIELAB> = colors.iter().map(|c| c.to_color()).collect();
+        let mut min = f64::INFINITY;
+        for i in 0..labs.len() {
+            for j in i + 1..labs.len() {
+                min = min.min(metric.compute(&labs[i], &labs[j]));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn test_categorical_palette_constraints() -> Result<(), Error> {
+        let params = params(CategoricalDistance::DeltaE(DeltaE::CIEDE2000))?;
+        let palette = categorical_palette(&params);
+        assert_eq!(palette.len(), 8);
+        for color in &palette {
+            assert!(contrast_ratio(*color, params.background) >= 3.0);
+            assert!(Gamut::SRGB.contains(color));
+            let l = LchSpace::OKLCH.to_lch(color).0;
+            assert!((0.3 - 1e-9..0.7 + 1e-9).contains(&l));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_categorical_palette_is_reproducible() -> Result<(), Error> {
+        let params = params(CategoricalDistance::OKLab)?;
+        let first: Vec<String> = categorical_palette(&params)
+            .iter()
+            .map(|c| c.to_hex())
+            .collect::<Result<_, _>>()?;
+        let second: Vec<String> = categorical_palette(&params)
+            .iter()
+            .map(|c| c.to_hex())
+            .collect::<Result<_, _>>()?;
+        assert_eq!(first, second);
+        Ok(())
+    }
+
+    #[test]
+    fn test_categorical_palette_is_more_distinct_than_random() -> Result<(), Error> {
+        let params = params(CategoricalDistance::DeltaE(DeltaE::CIEDE2000))?;
+        let palette = categorical_palette(&params);
+        let mut random = params;
+        random.candidates = 8;
+        random.count = 8;
+        let unoptimized = categorical_palette(&random);
+        assert!(min_pairwise_distance(&palette) > min_pairwise_distance(&unoptimized));
+        Ok(())
+    }
+
+    #[test]
+    fn test_categorical_palette_impossible_constraints() -> Result<(), Error> {
+        let mut params = params(CategoricalDistance::OKLab)?;
+        params.min_contrast = 22.0;
+        assert!(categorical_palette(&params).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_categorical_palette_nan_background() -> Result<(), Error> {
+        let mut params = params(CategoricalDistance::DeltaE(DeltaE::CIEDE2000))?;
+        params.background = SRGB::from_vector3(Vector3::new(f64::NAN, 0.5, 0.5));
+        params.min_contrast = 0.0;
+        assert!(categorical_palette(&params).len() <= params.count);
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+
+use super::solve::solve_lightness_for_contrast;
+use crate::colors::{Color, LchSpace};
+use crate::gamut::Gamut;
+
+/// The lightness difference between a tone and its highlighted or emphasized neighbor.
+const TONE_STEP: f64 = 0.05;
+
+/// The hues of the accent colors of a generated palette, in radians.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Hues {
+    /// Use exactly these hues.
+    List(Vec<f64>),
+    /// Generate `count` hues starting at `hue` and spaced by the golden angle, so that any prefix of
+    /// the hues is spread evenly around the hue circle.
+    Seed { hue: f64, count: usize },
+}
+
+impl Hues {
+    fn to_vec(&self) -> Vec<f64> {
+        match self {
+            Hues::List(hues) => hues.clone(),
+            Hues::Seed { hue, count } => {
+                let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+                (0..*count)
+                    .map(|i| (hue + i as f64 * golden_angle).rem_euclid(2.0 * PI))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Parameters of a palette whose colors follow fixed lightness relationships, in the style of
+/// Solarized.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstrainedPaletteParams {
+    /// The lightness of the background tone, in [0, 1].
+    pub background_lightness: f64,
+    /// The WCAG 2 contrast ratio of body text against the background.
+    pub contrast: f64,
+    pub hues: Hues,
+    /// The hue used to tint the base tones, in radians.
+    pub base_hue: f64,
+    /// The chroma of the base tones, relative to the most saturated color of the same lightness
+    /// and hue within the gamut.
+    pub base_relative_chroma: f64,
+    pub space: LchSpace,
+    pub gamut: Gamut,
+}
+
+/// A palette generated by `constrained_palette`.
+#[derive(Clone, Debug)]
+pub struct ConstrainedPalette<C> {
+    /// Eight tones ordered from darkest to lightest, corresponding to Solarized's base03 through
+    /// base3. Lightnesses are symmetric, so tone `i` and tone `7 - i` have lightnesses that sum to
+    /// 1.0.
+    pub base: [C; 8],
+    /// One accent per hue, all with the same lightness and chroma.
+    pub accents: Vec<C>,
+}
+
+/// Generate a palette of base tones and accents with fixed lightness relationships.
+///
+/// The background tone has the requested lightness, and is accompanied by a highlight tone slightly
+/// closer to middle gray. The body text tone is solved to meet the contrast target against the
+/// background, and is accompanied by an emphasized tone slightly further from the background. These
+/// four tones are then mirrored around a lightness of 0.5 to give the tones of the opposite mode.
+///
+/// The accents share the lightness of the body text, and the largest chroma at which every accent
+/// hue fits within the gamut.
+pub fn constrained_palette<C>(params: &ConstrainedPaletteParams) -> ConstrainedPalette<C>
+where
+    C: Color,
+{
+    let space = params.space;
+    let gamut = params.gamut;
+    let tone = |l: f64| -> C {
+        let c = params.base_relative_chroma * gamut.max_chroma(space, l, params.base_hue);
+        space.from_lch(l, c, params.base_hue)
+    };
+
+    let background_l = params.background_lightness;
+    let is_dark = background_l < 0.5;
+    let toward_middle = if is_dark { TONE_STEP } else { -TONE_STEP };
+
+    let away = if is_dark { 1.0 } else { 0.0 };
+    let text: C = solve_lightness_for_contrast(
+        tone,
+        tone(background_l),
+        params.contrast,
+        background_l,
+        away,
+    );
+    let text_l = space.to_lch(&text).0;
+
+    // Solarized's base03, base02, base0, and base1 for a dark background, or base3, base2, base00,
+    // and base01 for a light one. The remaining tones are their mirror images.
+    let lightnesses = [
+        background_l,
+        background_l + toward_middle,
+        text_l,
+        (text_l + toward_middle).clamp(0.0, 1.0),
+    ];
+    let positions = if is_dark { [0, 1, 4, 5] } else { [7, 6, 3, 2] };
+
+    let mut base = [tone(0.0); 8];
+    for (position, l) in positions.iter().zip(lightnesses.iter()) {
+        base[*position] = tone(*l);
+        base[7 - position] = tone(1.0 - l);
+    }
+
+    let hues = params.hues.to_vec();
+    let chroma = hues
+        .iter()
+        .map(|h| gamut.max_chroma(space, text_l, *h))
+        .fold(f64::INFINITY, f64::min);
+    let accents = hues
+        .iter()
+        .map(|h| space.from_lch(text_l, chroma, *h))
+        .collect();
+
+    ConstrainedPalette { base, accents }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{DisplayP3, SRGB};
+    use crate::utils::contrast_ratio;
+
+    fn params(space: LchSpace, gamut: Gamut) -> ConstrainedPaletteParams {
+        ConstrainedPaletteParams {
+            background_lightness: 0.15,
+            contrast: 4.5,
+            hues: Hues::Seed { hue: 0.5, count: 8 },
+            base_hue: 3.8,
+            base_relative_chroma: 0.3,
+            space,
+            gamut,
+        }
+    }
+
+    #[test]
+    fn test_base_tones_are_symmetric() {
+        for space in &[LchSpace::CIELUV, LchSpace::OKLCH] {
+            let palette: ConstrainedPalette<SRGB> =
+                constrained_palette(&params(*space, Gamut::SRGB));
+            for i in 0..4 {
+                let dark = space.to_lch(&palette.base[i]).0;
+                let light = space.to_lch(&palette.base[7 - i]).0;
+                assert!((dark + light - 1.0).abs() < 1e-6);
+            }
+            let text_contrast = contrast_ratio(palette.base[4], palette.base[0]);
+            assert!((text_contrast - 4.5).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_accents_have_equal_lightness_and_fit_gamut() {
+        for space in &[LchSpace::CIELUV, LchSpace::OKLCH] {
+            let palette: ConstrainedPalette<SRGB> =
+                constrained_palette(&params(*space, Gamut::SRGB));
+            assert_eq!(palette.accents.len(), 8);
+            let text_l = space.to_lch(&palette.base[4]).0;
+            for accent in &palette.accents {
+                assert!((space.to_lch(accent).0 - text_l).abs() < 1e-6);
+                assert!(Gamut::SRGB.contains(accent));
+            }
+        }
+    }
+
+    #[test]
+    fn test_light_background() {
+        let mut light_params = params(LchSpace::OKLCH, Gamut::SRGB);
+        light_params.background_lightness = 0.85;
+        let palette: ConstrainedPalette<SRGB> = constrained_palette(&light_params);
+        let background_l = LchSpace::OKLCH.to_lch(&palette.base[7]).0;
+        assert!((background_l - 0.85).abs() < 1e-6);
+        let text_contrast = contrast_ratio(palette.base[3], palette.base[7]);
+        assert!((text_contrast - 4.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_emphasized_tone_is_further_from_background() {
+        let lightness = |palette: &ConstrainedPalette<SRGB>, i: usize| {
+            LchSpace::OKLCH.to_lch(&palette.base[i]).0
+        };
+        let dark: ConstrainedPalette<SRGB> =
+            constrained_palette(&params(LchSpace::OKLCH, Gamut::SRGB));
+        // base03 < base02 < base0 < base1
+        assert!(lightness(&dark, 0) < lightness(&dark, 1));
+        assert!(lightness(&dark, 1) < lightness(&dark, 4));
+        assert!(lightness(&dark, 4) < lightness(&dark, 5));
+
+        let mut light_params = params(LchSpace::OKLCH, Gamut::SRGB);
+        light_params.background_lightness = 0.85;
+        let light: ConstrainedPalette<SRGB> = constrained_palette(&light_params);
+        // base3 > base2 > base00 > base01
+        assert!(lightness(&light, 7) > lightness(&light, 6));
+        assert!(lightness(&light, 6) > lightness(&light, 3));
+        assert!(lightness(&light, 3) > lightness(&light, 2));
+    }
+
+    #[test]
+    fn test_display_p3_allows_more_chroma() {
+        let srgb: ConstrainedPalette<SRGB> =
+            constrained_palette(&params(LchSpace::OKLCH, Gamut::SRGB));
+        let display_p3: ConstrainedPalette<DisplayP3> =
+            constrained_palette(&params(LchSpace::OKLCH, Gamut::DisplayP3));
+        let srgb_chroma = LchSpace::OKLCH.to_lch(&srgb.accents[0]).1;
+        let display_p3_chroma = LchSpace::OKLCH.to_lch(&display_p3.accents[0]).1;
+        assert!(display_p3_chroma > srgb_chroma);
+        assert!(Gamut::DisplayP3.contains(&display_p3.accents[0]));
+    }
+}
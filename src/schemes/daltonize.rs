@@ -0,0 +1,180 @@
+use std::f64::consts::PI;
+
+use super::lint::cvd_delta_e;
+use super::{ColorScheme, LintConfig, SchemeColor};
+use crate::colors::{Color, LchSpace, CIELAB, SRGB};
+use crate::cvd::Deficiency;
+use crate::gamut::Gamut;
+
+/// The hue step between candidate replacements of a colliding color, in radians.
+const HUE_STEP: f64 = PI / 36.0;
+
+/// The chromas of candidate replacements, relative to the most saturated color of the same
+/// lightness and hue in the sRGB gamut.
+const RELATIVE_CHROMAS: [f64; 3] = [1.0, 0.75, 0.5];
+
+/// Adjust a color scheme so that its semantic pairs stay distinguishable under every simulated
+/// color vision deficiency.
+///
+/// For every pair in `config.semantic_pairs` that collides, the second color of the pair is
+/// replaced. Candidates are searched by rotating its hue and varying its chroma in CIELUV while
+/// keeping its lightness. CIELUV lightness depends only on luminance, so every contrast ratio
+/// involving the color is unchanged. Among the candidates that meet `config.warning_delta_e` for
+/// every pair the color belongs to, the one closest to the original color is chosen. If none meets
+/// it, the most distinguishable candidate is used instead.
+pub fn daltonize_scheme(scheme: &ColorScheme, config: &LintConfig) -> ColorScheme {
+    let mut result = *scheme;
+    for (first, second) in &config.semantic_pairs {
+        let collides = Deficiency::ALL.iter().any(|deficiency| {
+            cvd_delta_e(
+                &result.get(*first),
+                &result.get(*second),
+                *deficiency,
+                config,
+            ) < config.warning_delta_e
+        });
+        if collides {
+            let replacement = find_replacement(&result, *second, config);
+            result.set(*second, replacement);
+        }
+    }
+    result
+}
+
+fn find_replacement(scheme: &ColorScheme, color: SchemeColor, config: &LintConfig) -> SRGB {
+    let original = scheme.get(color);
+    let original_lab: CIELAB = original.to_color();
+    let space = LchSpace::CIELUV;
+    let (l, c, h) = space.to_lch(&original);
+
+    let mut best_distinct: Option<(f64, SRGB)> = None;
+    let mut most_distinct = (min_cvd_delta_e(scheme, color, &original, config), original);
+    for step in 0..72 {
+        let hue = h + step as f64 * HUE_STEP;
+        let max_chroma = Gamut::SRGB.max_chroma(space, l, hue);
+        let chromas = RELATIVE_CHROMAS
+            .iter()
+            .map(|relative| relative * max_chroma)
+            .chain(Some(c.min(max_chroma)));
+        for chroma in chromas {
+            let candidate: SRGB = space.from_lch(l, chroma, hue);
+            let distinctness = min_cvd_delta_e(scheme, color, &candidate, config);
+            if distinctness >= config.warning_delta_e {
+                let change = config.metric.compute(&original_lab, &candidate.to_color());
+                if best_distinct.map_or(true, |(best, _)| change < best) {
+                    best_distinct = Some((change, candidate));
+                }
+            } else if distinctness > most_distinct.0 {
+                most_distinct = (distinctness, candidate);
+            }
+        }
+    }
+    best_distinct.map_or(most_distinct.1, |(_, candidate)| candidate)
+}
+
+/// The smallest simulated color difference between `candidate`, standing in for `color`, and the
+/// other color of every semantic pair that `color` belongs to.
+fn min_cvd_delta_e(
+    scheme: &ColorScheme,
+    color: SchemeColor,
+    candidate: &SRGB,
+    config: &LintConfig,
+) -> f64 {
+    let others = config
+        .semantic_pairs
+        .iter()
+        .filter_map(|(first, second)| {
+            if *first == color {
+                Some(*second)
+            } else if *second == color {
+                Some(*first)
+            } else {
+                None
+            }
+        })
+        .filter(|other| *other != color);
+    let mut min = f64::INFINITY;
+    for other in others {
+        for deficiency in Deficiency::ALL.iter() {
+            min = min.min(cvd_delta_e(
+                candidate,
+                &scheme.get(other),
+                *deficiency,
+                config,
+            ));
+        }
+    }
+    min
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    use crate::schemes::{lint_scheme, AnsiColor, DiagnosticKind};
+    use crate::terminal::ansi_16_palette;
+    use crate::utils::contrast_ratio;
+
+    /// A scheme whose red and green are nearly indistinguishable with deuteranopia.
+    fn colliding_scheme() -> Result<ColorScheme, Error> {
+        let mut ansi = ansi_16_palette();
+        ansi[AnsiColor::Red.index()] = SRGB::from_hex("b5651d")?;
+        ansi[AnsiColor::Green.index()] = SRGB::from_hex("7f7f1f")?;
+        Ok(ColorScheme {
+            foreground: SRGB::from_hex("e5e5e5")?,
+            background: SRGB::from_hex("000000")?,
+            cursor: SRGB::from_hex("ffffff")?,
+            ansi,
+        })
+    }
+
+    fn cvd_collisions(scheme: &ColorScheme, config: &LintConfig) -> Vec<DiagnosticKind> {
+        lint_scheme(scheme, config)
+            .into_iter()
+            .map(|d| d.kind)
+            .filter(|kind| matches!(kind, DiagnosticKind::CvdCollision { .. }))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_reports_cvd_collision() -> Result<(), Error> {
+        let collisions = cvd_collisions(&colliding_scheme()?, &LintConfig::default());
+        assert!(collisions.iter().any(|kind| match kind {
+            DiagnosticKind::CvdCollision { first, second, .. } => {
+                *first == SchemeColor::Ansi(AnsiColor::Red)
+                    && *second == SchemeColor::Ansi(AnsiColor::Green)
+            }
+            _ => false,
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_daltonize_resolves_collisions() -> Result<(), Error> {
+        let config = LintConfig::default();
+        let scheme = colliding_scheme()?;
+        let fixed = daltonize_scheme(&scheme, &config);
+        assert!(cvd_collisions(&fixed, &config).is_empty());
+
+        let green = SchemeColor::Ansi(AnsiColor::Green);
+        let before = contrast_ratio(scheme.get(green), scheme.background);
+        let after = contrast_ratio(fixed.get(green), fixed.background);
+        assert!((before - after).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_daltonize_keeps_distinguishable_scheme() -> Result<(), Error> {
+        let config = LintConfig::default();
+        let scheme = colliding_scheme()?;
+        let fixed = daltonize_scheme(&scheme, &config);
+        let again = daltonize_scheme(&fixed, &config);
+        for color in AnsiColor::ALL.iter() {
+            assert_eq!(
+                fixed.ansi_color(*color).to_hex()?,
+                again.ansi_color(*color).to_hex()?
+            );
+        }
+        Ok(())
+    }
+}
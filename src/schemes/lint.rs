@@ -0,0 +1,411 @@
+use std::cmp::Reverse;
+
+use super::{AnsiColor, ColorScheme, SchemeColor};
+use crate::colors::{Color, CIELAB, SRGB};
+use crate::cvd::{simulate_cvd, CvdModel, Deficiency};
+use crate::difference::DeltaE;
+use crate::utils::{apca_contrast, contrast_ratio};
+
+/// The contrast of one scheme color drawn on top of another.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContrastEntry {
+    pub foreground: SchemeColor,
+    pub background: SchemeColor,
+    /// WCAG 2 contrast ratio, between 1.0 and 21.0.
+    pub ratio: f64,
+    /// APCA lightness contrast (Lc).
+    pub apca: f64,
+}
+
+/// Compute the contrast of every foreground color on every background color of a scheme.
+///
+/// Foregrounds are the default foreground, the cursor, and the ANSI colors. Backgrounds are the
+/// default background and the ANSI colors, since applications commonly use ANSI colors for
+/// highlighting. Pairs of a color with itself are omitted.
+pub fn contrast_matrix(scheme: &ColorScheme) -> Vec<ContrastEntry> {
+    let ansi = AnsiColor::ALL.iter().map(|c| SchemeColor::Ansi(*c));
+    let foregrounds: Vec<SchemeColor> = [SchemeColor::Foreground, SchemeColor::Cursor]
+        .iter()
+        .cloned()
+        .chain(ansi.clone())
+        .collect();
+    let backgrounds: Vec<SchemeColor> = [SchemeColor::Background]
+        .iter()
+        .cloned()
+        .chain(ansi)
+        .collect();
+
+    let mut entries = Vec::new();
+    for background in &backgrounds {
+        for foreground in foregrounds.iter().filter(|f| *f != background) {
+            let fg = scheme.get(*foreground);
+            let bg = scheme.get(*background);
+            entries.push(ContrastEntry {
+                foreground: *foreground,
+                background: *background,
+                ratio: contrast_ratio(fg, bg),
+                apca: apca_contrast(fg, bg),
+            });
+        }
+    }
+    entries
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// The specific problem found by the linter, along with the measurements that triggered it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagnosticKind {
+    /// The WCAG 2 contrast ratio of a color against the background is too low.
+    LowContrastRatio { color: SchemeColor, ratio: f64 },
+    /// The APCA lightness contrast of a color against the background is too low.
+    LowApcaContrast { color: SchemeColor, apca: f64 },
+    /// Two ANSI colors are too similar to tell apart.
+    Indistinguishable {
+        first: AnsiColor,
+        second: AnsiColor,
+        delta_e: f64,
+    },
+    /// A bright ANSI color is not lighter than its normal counterpart.
+    BrightNotLighter {
+        normal: AnsiColor,
+        bright: AnsiColor,
+        normal_lightness: f64,
+        bright_lightness: f64,
+    },
+    /// Two colors with different meanings are too similar to tell apart for people with a color
+    /// vision deficiency.
+    CvdCollision {
+        deficiency: Deficiency,
+        first: SchemeColor,
+        second: SchemeColor,
+        delta_e: f64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    pub message: String,
+}
+
+/// Thresholds used by `lint_scheme`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintConfig {
+    /// Contrast ratios against the background below this are errors.
+    pub error_contrast_ratio: f64,
+    /// Contrast ratios against the background below this are warnings.
+    pub warning_contrast_ratio: f64,
+    /// Absolute APCA contrasts against the background below this are warnings.
+    pub warning_apca_contrast: f64,
+    /// Color differences between ANSI colors below this are errors.
+    pub error_delta_e: f64,
+    /// Color differences between ANSI colors below this are warnings.
+    pub warning_delta_e: f64,
+    pub metric: DeltaE,
+    /// ANSI colors that are not expected to be readable on the background, such as black in a
+    /// dark scheme, which usually matches the background.
+    pub contrast_exemptions: Vec<AnsiColor>,
+    /// Pairs of colors with different meanings, such as errors and successes, that must remain
+    /// distinguishable under every color vision deficiency. The ΔE thresholds above apply.
+    pub semantic_pairs: Vec<(SchemeColor, SchemeColor)>,
+    pub cvd_model: CvdModel,
+    /// The severity of the simulated color vision deficiencies, from 0.0 to 1.0.
+    pub cvd_severity: f64,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            error_contrast_ratio: 3.0,
+            warning_contrast_ratio: 4.5,
+            warning_apca_contrast: 45.0,
+            error_delta_e: 5.0,
+            warning_delta_e: 10.0,
+            metric: DeltaE::CIEDE2000,
+            contrast_exemptions: vec![AnsiColor::Black],
+            semantic_pairs: default_semantic_pairs(),
+            cvd_model: CvdModel::Machado2009,
+            cvd_severity: 1.0,
+        }
+    }
+}
+
+/// Error versus success (red and green), warning versus error (yellow and red), and diff deletion
+/// versus addition (bright red and bright green).
+fn default_semantic_pairs() -> Vec<(SchemeColor, SchemeColor)> {
+    vec![
+        (
+            SchemeColor::Ansi(AnsiColor::Red),
+            SchemeColor::Ansi(AnsiColor::Green),
+        ),
+        (
+            SchemeColor::Ansi(AnsiColor::Yellow),
+            SchemeColor::Ansi(AnsiColor::Red),
+        ),
+        (
+            SchemeColor::Ansi(AnsiColor::BrightRed),
+            SchemeColor::Ansi(AnsiColor::BrightGreen),
+        ),
+    ]
+}
+
+/// Check a color scheme for common readability problems.
+///
+/// Diagnostics are sorted from most to least severe.
+pub fn lint_scheme(scheme: &ColorScheme, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    lint_contrast(scheme, config, &mut diagnostics);
+    lint_distinguishability(scheme, config, &mut diagnostics);
+    lint_bright_variants(scheme, &mut diagnostics);
+    lint_cvd_collisions(scheme, config, &mut diagnostics);
+    diagnostics.sort_by_key(|d| Reverse(d.severity));
+    diagnostics
+}
+
+fn lint_contrast(scheme: &ColorScheme, config: &LintConfig, diagnostics: &mut Vec<Diagnostic>) {
+    let entries = contrast_matrix(scheme);
+    let on_background = entries.iter().filter(|entry| {
+        entry.background == SchemeColor::Background
+            && match entry.foreground {
+                SchemeColor::Ansi(ansi) => !config.contrast_exemptions.contains(&ansi),
+                _ => true,
+            }
+    });
+    for entry in on_background {
+        if entry.ratio < config.warning_contrast_ratio {
+            let severity = if entry.ratio < config.error_contrast_ratio {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            diagnostics.push(Diagnostic {
+                severity,
+                kind: DiagnosticKind::LowContrastRatio {
+                    color: entry.foreground,
+                    ratio: entry.ratio,
+                },
+                message: format!(
+                    "{:?} has a contrast ratio of {:.2} against the background",
+                    entry.foreground, entry.ratio
+                ),
+            });
+        }
+        if entry.apca.abs() < config.warning_apca_contrast {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                kind: DiagnosticKind::LowApcaContrast {
+                    color: entry.foreground,
+                    apca: entry.apca,
+                },
+                message: format!(
+                    "{:?} has an APCA contrast of Lc {:.1} against the background",
+                    entry.foreground, entry.apca
+                ),
+            });
+        }
+    }
+}
+
+/// Compare every pair of normal colors and every pair of bright colors, since a normal color and
+/// its own bright variant are expected to be similar.
+fn lint_distinguishability(
+    scheme: &ColorScheme,
+    config: &LintConfig,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let labs: Vec<CIELAB> = scheme.ansi.iter().map(|c| c.to_color()).collect();
+    for (i, first) in AnsiColor::ALL.iter().enumerate() {
+        for second in AnsiColor::ALL[i + 1..]
+            .iter()
+            .filter(|c| c.is_bright() == first.is_bright())
+        {
+            let delta_e = config
+                .metric
+                .compute(&labs[first.index()], &labs[second.index()]);
+            if delta_e >= config.warning_delta_e {
+                continue;
+            }
+            let severity = if delta_e < config.error_delta_e {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            diagnostics.push(Diagnostic {
+                severity,
+                kind: DiagnosticKind::Indistinguishable {
+                    first: *first,
+                    second: *second,
+                    delta_e,
+                },
+                message: format!(
+                    "{:?} and {:?} differ by only ΔE {:.1}",
+                    first, second, delta_e
+                ),
+            });
+        }
+    }
+}
+
+fn lint_bright_variants(scheme: &ColorScheme, diagnostics: &mut Vec<Diagnostic>) {
+    for normal in AnsiColor::ALL.iter().filter(|c| !c.is_bright()) {
+        let bright = AnsiColor::ALL[normal.index() + 8];
+        let normal_lightness = lightness(&scheme.ansi_color(*normal));
+        let bright_lightness = lightness(&scheme.ansi_color(bright));
+        if bright_lightness <= normal_lightness {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                kind: DiagnosticKind::BrightNotLighter {
+                    normal: *normal,
+                    bright,
+                    normal_lightness,
+                    bright_lightness,
+                },
+                message: format!(
+                    "{:?} (L* {:.1}) is not lighter than {:?} (L* {:.1})",
+                    bright,
+                    bright_lightness * 100.0,
+                    normal,
+                    normal_lightness * 100.0
+                ),
+            });
+        }
+    }
+}
+
+fn lint_cvd_collisions(
+    scheme: &ColorScheme,
+    config: &LintConfig,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (first, second) in &config.semantic_pairs {
+        for deficiency in Deficiency::ALL.iter() {
+            let delta_e = cvd_delta_e(
+                &scheme.get(*first),
+                &scheme.get(*second),
+                *deficiency,
+                config,
+            );
+            if delta_e >= config.warning_delta_e {
+                continue;
+            }
+            let severity = if delta_e < config.error_delta_e {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            diagnostics.push(Diagnostic {
+                severity,
+                kind: DiagnosticKind::CvdCollision {
+                    deficiency: *deficiency,
+                    first: *first,
+                    second: *second,
+                    delta_e,
+                },
+                message: format!(
+                    "{:?} and {:?} differ by only ΔE {:.1} under {:?} color vision deficiency",
+                    first, second, delta_e, deficiency
+                ),
+            });
+        }
+    }
+}
+
+/// The color difference between two colors as seen with a simulated color vision deficiency.
+pub(super) fn cvd_delta_e(
+    first: &SRGB,
+    second: &SRGB,
+    deficiency: Deficiency,
+    config: &LintConfig,
+) -> f64 {
+    let simulate = |color: &SRGB| -> CIELAB {
+        simulate_cvd(color, deficiency, config.cvd_severity, config.cvd_model).to_color()
+    };
+    config.metric.compute(&simulate(first), &simulate(second))
+}
+
+fn lightness(color: &SRGB) -> f64 {
+    let lab: CIELAB = color.to_color();
+    lab.to_vector3()[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    use crate::terminal::ansi_16_palette;
+
+    fn xterm_scheme() -> Result<ColorScheme, Error> {
+        Ok(ColorScheme {
+            foreground: SRGB::from_hex("e5e5e5")?,
+            background: SRGB::from_hex("000000")?,
+            cursor: SRGB::from_hex("ffffff")?,
+            ansi: ansi_16_palette(),
+        })
+    }
+
+    #[test]
+    fn test_contrast_matrix_size() -> Result<(), Error> {
+        // 18 foregrounds on the background, and 17 on each ANSI color
+        assert_eq!(contrast_matrix(&xterm_scheme()?).len(), 18 + 16 * 17);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_unreadable_bright_black() -> Result<(), Error> {
+        let mut scheme = xterm_scheme()?;
+        scheme.ansi[AnsiColor::BrightBlack.index()] = SRGB::from_hex("1a1a1a")?;
+        let diagnostics = lint_scheme(&scheme, &LintConfig::default());
+        assert!(diagnostics.iter().any(|d| d.severity == Severity::Error
+            && d.kind
+                == DiagnosticKind::LowContrastRatio {
+                    color: SchemeColor::Ansi(AnsiColor::BrightBlack),
+                    ratio: contrast_ratio(scheme.ansi[8], scheme.background),
+                }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_similar_red_and_magenta() -> Result<(), Error> {
+        let mut scheme = xterm_scheme()?;
+        scheme.ansi[AnsiColor::Magenta.index()] = SRGB::from_hex("cd0010")?;
+        let diagnostics = lint_scheme(&scheme, &LintConfig::default());
+        let found = diagnostics.iter().find(|d| match d.kind {
+            DiagnosticKind::Indistinguishable { first, second, .. } => {
+                first == AnsiColor::Red && second == AnsiColor::Magenta
+            }
+            _ => false,
+        });
+        assert_eq!(found.map(|d| d.severity), Some(Severity::Error));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_bright_not_lighter() -> Result<(), Error> {
+        let mut scheme = xterm_scheme()?;
+        scheme.ansi[AnsiColor::BrightGreen.index()] = SRGB::from_hex("008000")?;
+        let diagnostics = lint_scheme(&scheme, &LintConfig::default());
+        assert!(diagnostics.iter().any(|d| match d.kind {
+            DiagnosticKind::BrightNotLighter { bright, .. } => bright == AnsiColor::BrightGreen,
+            _ => false,
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_sorted_by_severity() -> Result<(), Error> {
+        let mut scheme = xterm_scheme()?;
+        scheme.ansi[AnsiColor::Blue.index()] = SRGB::from_hex("000010")?;
+        let diagnostics = lint_scheme(&scheme, &LintConfig::default());
+        assert!(diagnostics
+            .windows(2)
+            .all(|pair| pair[0].severity >= pair[1].severity));
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+mod categorical;
+mod constrained;
+mod daltonize;
+mod lint;
+mod scheme;
+mod solve;
+mod variant;
+
+pub use categorical::{categorical_palette, CategoricalDistance, CategoricalPaletteParams};
+pub use constrained::{constrained_palette, ConstrainedPalette, ConstrainedPaletteParams, Hues};
+pub use daltonize::daltonize_scheme;
+pub use lint::{
+    contrast_matrix, lint_scheme, ContrastEntry, Diagnostic, DiagnosticKind, LintConfig, Severity,
+};
+pub use scheme::{AnsiColor, ColorScheme, SchemeColor};
+pub use variant::mirror_scheme;
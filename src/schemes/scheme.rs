@@ -0,0 +1,98 @@
+use crate::colors::SRGB;
+
+/// One of the 16 colors of the ANSI terminal palette, in palette order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AnsiColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl AnsiColor {
+    /// All colors, ordered by palette index.
+    pub const ALL: [AnsiColor; 16] = [
+        AnsiColor::Black,
+        AnsiColor::Red,
+        AnsiColor::Green,
+        AnsiColor::Yellow,
+        AnsiColor::Blue,
+        AnsiColor::Magenta,
+        AnsiColor::Cyan,
+        AnsiColor::White,
+        AnsiColor::BrightBlack,
+        AnsiColor::BrightRed,
+        AnsiColor::BrightGreen,
+        AnsiColor::BrightYellow,
+        AnsiColor::BrightBlue,
+        AnsiColor::BrightMagenta,
+        AnsiColor::BrightCyan,
+        AnsiColor::BrightWhite,
+    ];
+
+    /// The index of this color in the terminal palette.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn is_bright(self) -> bool {
+        self.index() >= 8
+    }
+}
+
+/// A named color within a color scheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SchemeColor {
+    Foreground,
+    Background,
+    Cursor,
+    Ansi(AnsiColor),
+}
+
+/// A terminal color scheme.
+///
+/// A scheme consists of the default foreground and background colors, the cursor color, and the
+/// 16 ANSI palette colors.
+#[derive(Clone, Copy, Debug)]
+pub struct ColorScheme {
+    pub foreground: SRGB,
+    pub background: SRGB,
+    pub cursor: SRGB,
+    pub ansi: [SRGB; 16],
+}
+
+impl ColorScheme {
+    pub fn ansi_color(&self, color: AnsiColor) -> SRGB {
+        self.ansi[color.index()]
+    }
+
+    pub fn get(&self, color: SchemeColor) -> SRGB {
+        match color {
+            SchemeColor::Foreground => self.foreground,
+            SchemeColor::Background => self.background,
+            SchemeColor::Cursor => self.cursor,
+            SchemeColor::Ansi(ansi) => self.ansi_color(ansi),
+        }
+    }
+
+    pub fn set(&mut self, color: SchemeColor, value: SRGB) {
+        match color {
+            SchemeColor::Foreground => self.foreground = value,
+            SchemeColor::Background => self.background = value,
+            SchemeColor::Cursor => self.cursor = value,
+            SchemeColor::Ansi(ansi) => self.ansi[ansi.index()] = value,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::colors::Color;
+use crate::utils::contrast_ratio;
+
+/// The number of bisection steps used when solving for a lightness.
+const ITERATIONS: usize = 40;
+
+/// Find the lightness between `toward` and `away` at which a color reaches a contrast ratio
+/// against a background.
+///
+/// `toward` should be a lightness with little contrast (usually the background's own lightness)
+/// and `away` the extreme with the most contrast. If the target cannot be reached, the color at
+/// `away` is returned.
+pub(super) fn solve_lightness_for_contrast<C, F>(
+    candidate: F,
+    background: C,
+    target: f64,
+    mut toward: f64,
+    mut away: f64,
+) -> C
+where
+    C: Color,
+    F: Fn(f64) -> C,
+{
+    let contrast = |l: f64| contrast_ratio(candidate(l), background);
+    if contrast(away) <= target {
+        return candidate(away);
+    }
+    for _ in 0..ITERATIONS {
+        let mid = (toward + away) / 2.0;
+        if contrast(mid) >= target {
+            away = mid;
+        } else {
+            toward = mid;
+        }
+    }
+    candidate(away)
+}
@@ -0,0 +1,166 @@
+use super::solve::solve_lightness_for_contrast;
+use super::ColorScheme;
+use crate::colors::{Color, LchSpace, SRGB};
+use crate::gamut::Gamut;
+use crate::utils::contrast_ratio;
+
+/// A color described by its lightness, hue, and chroma relative to the most saturated color of
+/// that lightness and hue in the sRGB gamut.
+///
+/// Relative chroma is used so that a vivid color stays vivid after its lightness changes, without
+/// leaving the gamut.
+#[derive(Clone, Copy, Debug)]
+struct RelativeLch {
+    l: f64,
+    relative_chroma: f64,
+    h: f64,
+}
+
+impl RelativeLch {
+    fn from_srgb(color: &SRGB, space: LchSpace) -> Self {
+        let (l, c, h) = space.to_lch(color);
+        let max_chroma = Gamut::SRGB.max_chroma(space, l, h);
+        let relative_chroma = if max_chroma > 0.0 {
+            (c / max_chroma).min(1.0)
+        } else {
+            0.0
+        };
+        RelativeLch {
+            l,
+            relative_chroma,
+            h,
+        }
+    }
+
+    fn to_srgb(self, space: LchSpace) -> SRGB {
+        let c = self.relative_chroma * Gamut::SRGB.max_chroma(space, self.l, self.h);
+        space.from_lch(self.l, c, self.h)
+    }
+
+    fn with_lightness(self, l: f64) -> Self {
+        RelativeLch { l, ..self }
+    }
+}
+
+/// Derive the opposite variant of a color scheme, such as a light scheme from a dark one.
+///
+/// The lightness of every color is mirrored in the given perceptual space while hues and relative
+/// chroma are kept. Each color other than the background is then re-solved so that its contrast
+/// ratio against the new background matches its contrast ratio against the original background,
+/// as closely as the gamut allows.
+///
+/// The same operation derives a dark scheme from a light one.
+pub fn mirror_scheme(scheme: &ColorScheme, space: LchSpace) -> ColorScheme {
+    let background = RelativeLch::from_srgb(&scheme.background, space);
+    let new_background = background.with_lightness(1.0 - background.l).to_srgb(space);
+
+    let solve = |color: &SRGB| solve_contrast(color, &scheme.background, &new_background, space);
+
+    let mut ansi = scheme.ansi;
+    for color in ansi.iter_mut() {
+        *color = solve(color);
+    }
+
+    ColorScheme {
+        foreground: solve(&scheme.foreground),
+        background: new_background,
+        cursor: solve(&scheme.cursor),
+        ansi,
+    }
+}
+
+/// Find a mirrored color whose contrast against the new background matches the contrast of the
+/// original color against the original background.
+///
+/// A color that was lighter than the original background becomes darker than the new one, and vice
+/// versa. If the target contrast cannot be reached, the most contrasting lightness is used.
+fn solve_contrast(color: &SRGB, background: &SRGB, new_background: &SRGB, space: LchSpace) -> SRGB {
+    let target = contrast_ratio(*color, *background);
+    let relative = RelativeLch::from_srgb(color, space);
+    let was_lighter = color.to_ciexyz().get_luminance() > background.to_ciexyz().get_luminance();
+
+    let candidate = |l: f64| relative.with_lightness(l).to_srgb(space);
+    let away = if was_lighter { 0.0 } else { 1.0 };
+    solve_lightness_for_contrast(
+        candidate,
+        *new_background,
+        target,
+        space.to_lch(new_background).0,
+        away,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+    use crate::schemes::{AnsiColor, SchemeColor};
+    use crate::terminal::ansi_16_palette;
+
+    fn dark_scheme() -> Result<ColorScheme, Error> {
+        Ok(ColorScheme {
+            foreground: SRGB::from_hex("e5e5e5")?,
+            background: SRGB::from_hex("1d1f21")?,
+            cursor: SRGB::from_hex("ffffff")?,
+            ansi: ansi_16_palette(),
+        })
+    }
+
+    fn lightness(color: &SRGB, space: LchSpace) -> f64 {
+        space.to_lch(color).0
+    }
+
+    #[test]
+    fn test_mirror_dark_to_light() -> Result<(), Error> {
+        let dark = dark_scheme()?;
+        let black = SRGB::from_hex("000")?;
+        for space in &[LchSpace::CIELUV, LchSpace::OKLCH] {
+            let light = mirror_scheme(&dark, *space);
+            let original_l = lightness(&dark.background, *space);
+            assert!((lightness(&light.background, *space) - (1.0 - original_l)).abs() < 1e-3);
+
+            // Colors lighter than the dark background become darker than the light background,
+            // so the best achievable contrast is that of black.
+            let reachable = contrast_ratio(black, light.background);
+            let colors = [
+                SchemeColor::Foreground,
+                SchemeColor::Ansi(AnsiColor::Red),
+                SchemeColor::Ansi(AnsiColor::Blue),
+                SchemeColor::Ansi(AnsiColor::BrightYellow),
+            ];
+            for color in colors.iter() {
+                let before = contrast_ratio(dark.get(*color), dark.background);
+                let after = contrast_ratio(light.get(*color), light.background);
+                assert!(
+                    (before.min(reachable) - after).abs() < 1e-3,
+                    "{:?}: {} vs {}",
+                    color,
+                    before,
+                    after
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_mirror_keeps_hue() -> Result<(), Error> {
+        let dark = dark_scheme()?;
+        let light = mirror_scheme(&dark, LchSpace::OKLCH);
+        let (_, _, hue_before) = LchSpace::OKLCH.to_lch(&dark.ansi_color(AnsiColor::Red));
+        let (_, _, hue_after) = LchSpace::OKLCH.to_lch(&light.ansi_color(AnsiColor::Red));
+        assert!((hue_before - hue_after).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mirror_light_to_dark() -> Result<(), Error> {
+        let light = mirror_scheme(&dark_scheme()?, LchSpace::CIELUV);
+        let dark = mirror_scheme(&light, LchSpace::CIELUV);
+        assert_eq!(dark.background.to_hex()?, "1d1f21");
+        let before = contrast_ratio(light.foreground, light.background);
+        let after = contrast_ratio(dark.foreground, dark.background);
+        assert!((before - after).abs() < 1e-3);
+        Ok(())
+    }
+}
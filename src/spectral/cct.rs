@@ -0,0 +1,311 @@
+use nalgebra::Vector3;
+
+use super::{blackbody_chromaticity, Observer};
+use crate::colors::{Color, CIE1960UCS, CIEXYY};
+use crate::errors::Error;
+use crate::errors::Error::ColorTemperatureError;
+
+/// The range of correlated color temperatures, in kelvins, searched by `CctMethod::Ohno2013`.
+pub const OHNO_CCT_RANGE: (f64, f64) = (1000.0, 100_000.0);
+
+/// The ratio between neighboring temperatures of the initial table of `CctMethod::Ohno2013`.
+const OHNO_STEP: f64 = 1.01;
+
+/// The number of times the table of `CctMethod::Ohno2013` is refined around the closest
+/// temperature. Each refinement makes the table ten times finer.
+const OHNO_CASCADES: usize = 3;
+
+/// Chromaticities further than this from the Planckian locus in CIE 1960 UCS have no meaningful
+/// correlated color temperature.
+const MAX_DUV: f64 = 0.05;
+
+/// A method of computing the correlated color temperature of a chromaticity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CctMethod {
+    /// Ohno, "Practical Use and Calculation of CCT and Duv" (2013). Searches a table of Planckian
+    /// chromaticities, then interpolates the closest point on the locus. This is the most accurate
+    /// method, to within about 0.1 K, for temperatures from 1000 K to 100,000 K.
+    Ohno2013,
+    /// McCamy, "Correlated color temperature as an explicit function of chromaticity coordinates"
+    /// (1992). A cubic approximation in x and y that is accurate to within a few kelvins from
+    /// 2856 K to 6504 K, and much less accurate elsewhere.
+    McCamy1992,
+    /// Robertson, "Computation of Correlated Color Temperature and Distribution Temperature"
+    /// (1968). Interpolates between 31 tabulated isotemperature lines, and is accurate to within a
+    /// few kelvins above 1667 K.
+    Robertson1968,
+}
+
+/// Compute the correlated color temperature (CCT), in kelvins, and the distance from the Planckian
+/// locus (Duv) of a chromaticity.
+///
+/// Both are defined in the CIE 1960 UCS for the CIE 1931 2° observer. Duv is positive above the
+/// locus, towards green, and negative below it, towards magenta. The luminance of the color is
+/// ignored.
+pub fn cct_duv(color: &CIEXYY, method: CctMethod) -> Result<(f64, f64), Error> {
+    let uv = uv_from_xyy(color);
+    let (cct, duv) = match method {
+        CctMethod::Ohno2013 => ohno_2013(uv)?,
+        CctMethod::McCamy1992 => {
+            let v = color.to_vector3();
+            let n = (v[0] - 0.3320) / (0.1858 - v[1]);
+            let cct = 449.0 * n.powi(3) + 3525.0 * n.powi(2) + 6823.3 * n + 5520.33;
+            if cct.is_nan() || cct <= 0.0 {
+                return Err(ColorTemperatureError(format!(
+                    "McCamy's approximation gives no temperature for x = {}, y = {}",
+                    v[0], v[1]
+                )));
+            }
+            (cct, signed_distance(uv, planckian_uv(cct)?))
+        }
+        CctMethod::Robertson1968 => robertson_1968(uv)?,
+    };
+    if duv.abs() > MAX_DUV {
+        return Err(ColorTemperatureError(format!(
+            "The chromaticity is {:.4} from the Planckian locus, which is too far for a \
+             meaningful color temperature",
+            duv
+        )));
+    }
+    Ok((cct, duv))
+}
+
+/// Compute the chromaticity with a correlated color temperature, in kelvins, and a distance from
+/// the Planckian locus (Duv). This is the inverse of `cct_duv`. The returned color has luminance
+/// Y = 1.0.
+pub fn chromaticity_from_cct_duv(cct: f64, duv: f64) -> Result<CIEXYY, Error> {
+    let on_locus = planckian_uv(cct)?;
+    if duv == 0.0 {
+        return Ok(xyy_from_uv(on_locus));
+    }
+    let tangent = planckian_uv(cct * (1.0 + 1e-6))? - on_locus;
+    let mut normal = Vector3::new(-tangent[1], tangent[0], 0.0).normalize();
+    if normal[1] < 0.0 {
+        normal = -normal;
+    }
+    Ok(xyy_from_uv(on_locus + normal * duv))
+}
+
+fn ohno_2013(uv: Vector3<f64>) -> Result<(f64, f64), Error> {
+    let (low, high) = OHNO_CCT_RANGE;
+    let count = ((high / low).ln() / OHNO_STEP.ln()).ceil() as usize + 1;
+    let mut temperatures: Vec<f64> = (0..count)
+        .map(|i| (low * OHNO_STEP.powi(i as i32)).min(high))
+        .collect();
+    let mut table = ohno_table(&temperatures, uv)?;
+    let mut closest = closest_index(&table);
+    for _ in 0..OHNO_CASCADES {
+        let lower = table[closest.saturating_sub(1)].0;
+        let upper = table[(closest + 1).min(table.len() - 1)].0;
+        temperatures = (0..=20)
+            .map(|i| lower + (upper - lower) * i as f64 / 20.0)
+            .collect();
+        table = ohno_table(&temperatures, uv)?;
+        closest = closest_index(&table);
+    }
+    if closest == 0 || closest == table.len() - 1 {
+        return Err(ColorTemperatureError(format!(
+            "The correlated color temperature is outside of {} K to {} K",
+            low, high
+        )));
+    }
+
+    let (t0, uv0, d0) = table[closest - 1];
+    let (t1, _, d1) = table[closest];
+    let (t2, uv2, d2) = table[closest + 1];
+
+    // The triangular solution is more accurate close to the locus, and the parabolic solution
+    // further from it.
+    let l = (uv2 - uv0).norm();
+    let x = (d0 * d0 - d2 * d2 + l * l) / (2.0 * l);
+    let triangular_cct = t0 + (t2 - t0) * x / l;
+    let locus = uv0 + (uv2 - uv0) * x / l;
+    let sign = if uv[1] >= locus[1] { 1.0 } else { -1.0 };
+    let triangular_duv = sign * (d0 * d0 - x * x).max(0.0).sqrt();
+    if triangular_duv.abs() < 0.002 {
+        return Ok((triangular_cct, triangular_duv));
+    }
+
+    let denominator = (t2 - t1) * (t0 - t2) * (t1 - t0);
+    let a = (t0 * (d2 - d1) + t1 * (d0 - d2) + t2 * (d1 - d0)) / denominator;
+    let b = -(t0 * t0 * (d2 - d1) + t1 * t1 * (d0 - d2) + t2 * t2 * (d1 - d0)) / denominator;
+    let c = -(d0 * (t2 - t1) * t1 * t2 + d1 * (t0 - t2) * t0 * t2 + d2 * (t1 - t0) * t0 * t1)
+        / denominator;
+    let cct = -b / (2.0 * a);
+    Ok((cct, sign * (a * cct * cct + b * cct + c)))
+}
+
+/// Planckian chromaticities at each temperature, with their distances from `uv`.
+fn ohno_table(
+    temperatures: &[f64],
+    uv: Vector3<f64>,
+) -> Result<Vec<(f64, Vector3<f64>, f64)>, Error> {
+    temperatures
+        .iter()
+        .map(|t| {
+            let locus = planckian_uv(*t)?;
+            Ok((*t, locus, (uv - locus).norm()))
+        })
+        .collect()
+}
+
+fn closest_index(table: &[(f64, Vector3<f64>, f64)]) -> usize {
+    table
+        .iter()
+        .enumerate()
+        .min_by(|(_, (_, _, d1)), (_, (_, _, d2))| {
+            d1.partial_cmp(d2).expect("Distance is not NaN.")
+        })
+        .map(|(i, _)| i)
+        .expect("The table is not empty.")
+}
+
+fn robertson_1968(uv: Vector3<f64>) -> Result<(f64, f64), Error> {
+    let distance = |[_, u, v, t]: [f64; 4]| ((uv[1] - v) - t * (uv[0] - u)) / (1.0 + t * t).sqrt();
+    let mut previous = distance(ROBERTSON_ISOTHERMS[0]);
+    for pair in ROBERTSON_ISOTHERMS.windows(2) {
+        let current = distance(pair[1]);
+        if previous.signum() != current.signum() || current == 0.0 {
+            let fraction = previous / (previous - current);
+            let mired = pair[0][0] + (pair[1][0] - pair[0][0]) * fraction;
+            let locus = Vector3::new(
+                pair[0][1] + (pair[1][1] - pair[0][1]) * fraction,
+                pair[0][2] + (pair[1][2] - pair[0][2]) * fraction,
+                0.0,
+            );
+            return Ok((1e6 / mired, signed_distance(uv, locus)));
+        }
+        previous = current;
+    }
+    Err(ColorTemperatureError(String::from(
+        "Robertson's method requires a correlated color temperature of at least 1667 K",
+    )))
+}
+
+/// The distance between a chromaticity and a point on the Planckian locus, positive if the
+/// chromaticity is above the locus.
+fn signed_distance(uv: Vector3<f64>, locus: Vector3<f64>) -> f64 {
+    let distance = (uv - locus).norm();
+    if uv[1] >= locus[1] {
+        distance
+    } else {
+        -distance
+    }
+}
+
+/// The chromaticity of a blackbody in CIE 1960 UCS, with a third component of zero.
+fn planckian_uv(temperature: f64) -> Result<Vector3<f64>, Error> {
+    Ok(uv_from_xyy(&blackbody_chromaticity(
+        temperature,
+        Observer::CIE1931,
+    )?))
+}
+
+fn uv_from_xyy(color: &CIEXYY) -> Vector3<f64> {
+    let ucs: CIE1960UCS = color.to_color();
+    let v = ucs.to_vector3();
+    Vector3::new(v[0], v[1], 0.0)
+}
+
+fn xyy_from_uv(uv: Vector3<f64>) -> CIEXYY {
+    CIE1960UCS::from_vector3(Vector3::new(uv[0], uv[1], 1.0)).to_color()
+}
+
+/// Robertson's isotemperature lines: the reciprocal temperature in inverse megakelvins, the
+/// Planckian chromaticity u and v in CIE 1960 UCS, and the slope of the line.
+#[rustfmt::skip]
+const ROBERTSON_ISOTHERMS: [[f64; 4]; 31] = [
+    [0.0,   0.18006, 0.26352, -0.24341],
+    [10.0,  0.18066, 0.26589, -0.25479],
+    [20.0,  0.18133, 0.26846, -0.26876],
+    [30.0,  0.18208, 0.27119, -0.28539],
+    [40.0,  0.18293, 0.27407, -0.30470],
+    [50.0,  0.18388, 0.27709, -0.32675],
+    [60.0,  0.18494, 0.28021, -0.35156],
+    [70.0,  0.18611, 0.28342, -0.37915],
+    [80.0,  0.18740, 0.28668, -0.40955],
+    [90.0,  0.18880, 0.28997, -0.44278],
+    [100.0, 0.19032, 0.29326, -0.47888],
+    [125.0, 0.19462, 0.30141, -0.58204],
+    [150.0, 0.19962, 0.30921, -0.70471],
+    [175.0, 0.20525, 0.31647, -0.84901],
+    [200.0, 0.21142, 0.32312, -1.0182],
+    [225.0, 0.21807, 0.32909, -1.2168],
+    [250.0, 0.22511, 0.33439, -1.4512],
+    [275.0, 0.23247, 0.33904, -1.7298],
+    [300.0, 0.24010, 0.34308, -2.0637],
+    [325.0, 0.24792, 0.34655, -2.4681],
+    [350.0, 0.25591, 0.34951, -2.9641],
+    [375.0, 0.26400, 0.35200, -3.5814],
+    [400.0, 0.27218, 0.35407, -4.3633],
+    [425.0, 0.28039, 0.35577, -5.3762],
+    [450.0, 0.28863, 0.35714, -6.7262],
+    [475.0, 0.29685, 0.35823, -8.5955],
+    [500.0, 0.30505, 0.35907, -11.324],
+    [525.0, 0.31320, 0.35968, -15.628],
+    [550.0, 0.32129, 0.36011, -23.325],
+    [575.0, 0.32931, 0.36038, -40.770],
+    [600.0, 0.33724, 0.36051, -116.45],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral::daylight_chromaticity;
+
+    fn xyy(x: f64, y: f64) -> CIEXYY {
+        CIEXYY::from_vector3(Vector3::new(x, y, 1.0))
+    }
+
+    #[test]
+    fn test_d65() -> Result<(), Error> {
+        let d65 = xyy(0.31271, 0.32902);
+        let (cct, duv) = cct_duv(&d65, CctMethod::Ohno2013)?;
+        // The locus is computed from the 5 nm color matching functions, which moves the result by
+        // about a kelvin from that of the 1 nm tables.
+        assert!((cct - 6504.0).abs() < 2.0, "{}", cct);
+        assert!((duv - 0.0032).abs() < 1e-4, "{}", duv);
+        for method in &[CctMethod::McCamy1992, CctMethod::Robertson1968] {
+            let (cct, duv) = cct_duv(&d65, *method)?;
+            assert!((cct - 6504.0).abs() < 5.0, "{:?}: {}", method, cct);
+            assert!((duv - 0.0032).abs() < 2e-4, "{:?}: {}", method, duv);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_planckian_locus() -> Result<(), Error> {
+        for temperature in &[1500.0, 2856.0, 4000.0, 10000.0, 50000.0] {
+            let color = blackbody_chromaticity(*temperature, Observer::CIE1931)?;
+            let (cct, duv) = cct_duv(&color, CctMethod::Ohno2013)?;
+            assert!((cct - temperature).abs() / temperature < 1e-4, "{}", cct);
+            assert!(duv.abs() < 1e-6, "{}", duv);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<(), Error> {
+        for (temperature, target) in &[(2700.0, -0.01), (5000.0, 0.001), (6500.0, 0.02)] {
+            let color = chromaticity_from_cct_duv(*temperature, *target)?;
+            let (cct, duv) = cct_duv(&color, CctMethod::Ohno2013)?;
+            assert!((cct - temperature).abs() < 1.0, "{}", cct);
+            assert!((duv - target).abs() < 1e-5, "{}", duv);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_daylight_is_above_locus() -> Result<(), Error> {
+        let (_, duv) = cct_duv(&daylight_chromaticity(5003.0)?, CctMethod::Robertson1968)?;
+        assert!(duv > 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_far_from_locus() {
+        let green = xyy(0.3, 0.6);
+        assert!(cct_duv(&green, CctMethod::Ohno2013).is_err());
+        assert!(cct_duv(&green, CctMethod::Robertson1968).is_err());
+    }
+}
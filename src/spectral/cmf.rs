@@ -0,0 +1,220 @@
+use nalgebra::Vector3;
+
+/// A CIE standard colorimetric observer, defined by its color matching functions.
+///
+/// The color matching functions give the amount of each of the X, Y, and Z primaries needed to
+/// match a monochromatic stimulus of each wavelength.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Observer {
+    /// The CIE 1931 2° standard observer, for stimuli subtending a small field of view. This is the
+    /// observer assumed by `CIEXYZ` and every color space defined relative to it.
+    CIE1931,
+    /// The CIE 1964 10° supplementary standard observer, for stimuli subtending a larger field of
+    /// view.
+    CIE1964,
+}
+
+/// The wavelength range over which the color matching functions are tabulated, in nanometers.
+pub const WAVELENGTH_RANGE: (f64, f64) = (380.0, 780.0);
+
+impl Observer {
+    /// The spacing of the tabulated color matching functions, in nanometers.
+    pub fn interval(self) -> f64 {
+        match self {
+            Observer::CIE1931 => 5.0,
+            Observer::CIE1964 => 10.0,
+        }
+    }
+
+    /// The wavelengths at which the color matching functions are tabulated, in nanometers.
+    pub fn wavelengths(self) -> impl Iterator<Item = f64> {
+        let interval = self.interval();
+        (0..self.table().len()).map(move |i| WAVELENGTH_RANGE.0 + i as f64 * interval)
+    }
+
+    /// The values of the x̄, ȳ, and z̄ color matching functions at a wavelength in nanometers,
+    /// linearly interpolated between tabulated values. The functions are zero outside of
+    /// `WAVELENGTH_RANGE`.
+    pub fn color_matching_functions(self, wavelength: f64) -> Vector3<f64> {
+        let table = self.table();
+        let position = (wavelength - WAVELENGTH_RANGE.0) / self.interval();
+        if !(0.0..=(table.len() - 1) as f64).contains(&position) {
+            return Vector3::zeros();
+        }
+        let i = (position.floor() as usize).min(table.len() - 2);
+        let t = position - i as f64;
+        let lower = Vector3::from(table[i]);
+        let upper = Vector3::from(table[i + 1]);
+        lower * (1.0 - t) + upper * t
+    }
+
+    fn table(self) -> &'static [[f64; 3]] {
+        match self {
+            Observer::CIE1931 => &CIE1931_2_DEGREE,
+            Observer::CIE1964 => &CIE1964_10_DEGREE,
+        }
+    }
+}
+
+/// The CIE 1931 2° color matching functions from 380 nm to 780 nm in 5 nm steps, from CIE 15:2004.
+#[rustfmt::skip]
+const CIE1931_2_DEGREE: [[f64; 3]; 81] = [
+    [0.001368, 0.000039, 0.006450],
+    [0.002236, 0.000064, 0.010550],
+    [0.004243, 0.000120, 0.020050],
+    [0.007650, 0.000217, 0.036210],
+    [0.014310, 0.000396, 0.067850],
+    [0.023190, 0.000640, 0.110200],
+    [0.043510, 0.001210, 0.207400],
+    [0.077630, 0.002180, 0.371300],
+    [0.134380, 0.004000, 0.645600],
+    [0.214770, 0.007300, 1.039050],
+    [0.283900, 0.011600, 1.385600],
+    [0.328500, 0.016840, 1.622960],
+    [0.348280, 0.023000, 1.747060],
+    [0.348060, 0.029800, 1.782600],
+    [0.336200, 0.038000, 1.772110],
+    [0.318700, 0.048000, 1.744100],
+    [0.290800, 0.060000, 1.669200],
+    [0.251100, 0.073900, 1.528100],
+    [0.195360, 0.090980, 1.287640],
+    [0.142100, 0.112600, 1.041900],
+    [0.095640, 0.139020, 0.812950],
+    [0.057950, 0.169300, 0.616200],
+    [0.032010, 0.208020, 0.465180],
+    [0.014700, 0.258600, 0.353300],
+    [0.004900, 0.323000, 0.272000],
+    [0.002400, 0.407300, 0.212300],
+    [0.009300, 0.503000, 0.158200],
+    [0.029100, 0.608200, 0.111700],
+    [0.063270, 0.710000, 0.078250],
+    [0.109600, 0.793200, 0.057250],
+    [0.165500, 0.862000, 0.042160],
+    [0.225750, 0.914850, 0.029840],
+    [0.290400, 0.954000, 0.020300],
+    [0.359700, 0.980300, 0.013400],
+    [0.433450, 0.994950, 0.008750],
+    [0.512050, 1.000000, 0.005750],
+    [0.594500, 0.995000, 0.003900],
+    [0.678400, 0.978600, 0.002750],
+    [0.762100, 0.952000, 0.002100],
+    [0.842500, 0.915400, 0.001800],
+    [0.916300, 0.870000, 0.001650],
+    [0.978600, 0.816300, 0.001400],
+    [1.026300, 0.757000, 0.001100],
+    [1.056700, 0.694900, 0.001000],
+    [1.062200, 0.631000, 0.000800],
+    [1.045600, 0.566800, 0.000600],
+    [1.002600, 0.503000, 0.000340],
+    [0.938400, 0.441200, 0.000240],
+    [0.854450, 0.381000, 0.000190],
+    [0.751400, 0.321000, 0.000100],
+    [0.642400, 0.265000, 0.000050],
+    [0.541900, 0.217000, 0.000030],
+    [0.447900, 0.175000, 0.000020],
+    [0.360800, 0.138200, 0.000010],
+    [0.283500, 0.107000, 0.000000],
+    [0.218700, 0.081600, 0.000000],
+    [0.164900, 0.061000, 0.000000],
+    [0.121200, 0.044580, 0.000000],
+    [0.087400, 0.032000, 0.000000],
+    [0.063600, 0.023200, 0.000000],
+    [0.046770, 0.017000, 0.000000],
+    [0.032900, 0.011920, 0.000000],
+    [0.022700, 0.008210, 0.000000],
+    [0.015840, 0.005723, 0.000000],
+    [0.011359, 0.004102, 0.000000],
+    [0.008111, 0.002929, 0.000000],
+    [0.005790, 0.002091, 0.000000],
+    [0.004109, 0.001484, 0.000000],
+    [0.002899, 0.001047, 0.000000],
+    [0.002049, 0.000740, 0.000000],
+    [0.001440, 0.000520, 0.000000],
+    [0.001000, 0.000361, 0.000000],
+    [0.000690, 0.000249, 0.000000],
+    [0.000476, 0.000172, 0.000000],
+    [0.000332, 0.000120, 0.000000],
+    [0.000235, 0.000085, 0.000000],
+    [0.000166, 0.000060, 0.000000],
+    [0.000117, 0.000042, 0.000000],
+    [0.000083, 0.000030, 0.000000],
+    [0.000059, 0.000021, 0.000000],
+    [0.000042, 0.000015, 0.000000],
+];
+
+/// The CIE 1964 10° color matching functions from 380 nm to 780 nm in 10 nm steps, from CIE
+/// 15:2004.
+#[rustfmt::skip]
+const CIE1964_10_DEGREE: [[f64; 3]; 41] = [
+    [0.000160, 0.000017, 0.000705],
+    [0.002362, 0.000253, 0.010482],
+    [0.019110, 0.002004, 0.086011],
+    [0.084736, 0.008756, 0.389366],
+    [0.204492, 0.021391, 0.972542],
+    [0.314679, 0.038676, 1.553480],
+    [0.383734, 0.062077, 1.967280],
+    [0.370702, 0.089456, 1.994800],
+    [0.302273, 0.128201, 1.745370],
+    [0.195618, 0.185190, 1.317560],
+    [0.080507, 0.253589, 0.772125],
+    [0.016172, 0.339133, 0.415254],
+    [0.003816, 0.460777, 0.218502],
+    [0.037465, 0.606741, 0.112044],
+    [0.117749, 0.761757, 0.060709],
+    [0.236491, 0.875211, 0.030451],
+    [0.376772, 0.961988, 0.013676],
+    [0.529826, 0.991761, 0.003988],
+    [0.705224, 0.997340, 0.000000],
+    [0.878655, 0.955552, 0.000000],
+    [1.014160, 0.868934, 0.000000],
+    [1.118520, 0.777405, 0.000000],
+    [1.123990, 0.658341, 0.000000],
+    [1.030480, 0.527963, 0.000000],
+    [0.856297, 0.398057, 0.000000],
+    [0.647467, 0.283493, 0.000000],
+    [0.431567, 0.179828, 0.000000],
+    [0.268329, 0.107633, 0.000000],
+    [0.152568, 0.060281, 0.000000],
+    [0.081261, 0.031800, 0.000000],
+    [0.040851, 0.015905, 0.000000],
+    [0.019941, 0.007749, 0.000000],
+    [0.009577, 0.003718, 0.000000],
+    [0.004553, 0.001768, 0.000000],
+    [0.002175, 0.000846, 0.000000],
+    [0.001045, 0.000407, 0.000000],
+    [0.000508, 0.000199, 0.000000],
+    [0.000251, 0.000098, 0.000000],
+    [0.000126, 0.000050, 0.000000],
+    [0.000065, 0.000025, 0.000000],
+    [0.000033, 0.000013, 0.000000],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_energy_sums() {
+        // The color matching functions are normalized so that the equal-energy stimulus has
+        // equal X, Y, and Z.
+        for observer in &[Observer::CIE1931, Observer::CIE1964] {
+            let sum: Vector3<f64> = observer
+                .wavelengths()
+                .map(|wavelength| observer.color_matching_functions(wavelength))
+                .sum();
+            assert!((sum[0] / sum[1] - 1.0).abs() < 1e-4);
+            assert!((sum[2] / sum[1] - 1.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let observer = Observer::CIE1931;
+        assert_eq!(observer.color_matching_functions(555.0)[1], 1.0);
+        let between = observer.color_matching_functions(552.5);
+        assert!((between[1] - (0.99495 + 1.0) / 2.0).abs() < 1e-12);
+        assert_eq!(observer.color_matching_functions(780.0)[1], 0.000015);
+        assert_eq!(observer.color_matching_functions(300.0), Vector3::zeros());
+        assert_eq!(observer.color_matching_functions(800.0), Vector3::zeros());
+    }
+}
@@ -0,0 +1,234 @@
+use nalgebra::Vector3;
+
+use super::{Observer, SpectralDistribution};
+use crate::colors::{Color, CIEXYY, CIEXYZ};
+use crate::errors::Error;
+use crate::errors::Error::ColorTemperatureError;
+
+/// The range of correlated color temperatures, in kelvins, over which the CIE daylight locus is
+/// defined.
+pub const DAYLIGHT_CCT_RANGE: (f64, f64) = (4000.0, 25000.0);
+
+/// The second radiation constant of Planck's law, in meter kelvins, as used by CIE 15:2004.
+const C2: f64 = 1.4388e-2;
+
+/// Compute the chromaticity of a CIE daylight illuminant, such as D50 or D65, from its correlated
+/// color temperature in kelvins. The returned white point has luminance Y = 1.0.
+///
+/// The chromaticity is given by the CIE 1931 2° observer. Note that the named illuminants use
+/// temperatures from before the revision of Planck's constants, so D65 has a temperature of
+/// 6500 × 1.4388 / 1.438 ≈ 6504 K.
+pub fn daylight_chromaticity(cct: f64) -> Result<CIEXYY, Error> {
+    check_daylight_cct(cct)?;
+    let t = cct;
+    let x = if t <= 7000.0 {
+        -4.6070e9 / t.powi(3) + 2.9678e6 / t.powi(2) + 0.09911e3 / t + 0.244063
+    } else {
+        -2.0064e9 / t.powi(3) + 1.9018e6 / t.powi(2) + 0.24748e3 / t + 0.237040
+    };
+    let y = -3.0 * x * x + 2.870 * x - 0.275;
+    Ok(CIEXYY::from_vector3(Vector3::new(x, y, 1.0)))
+}
+
+/// Generate the relative spectral power distribution of a CIE daylight illuminant from its
+/// correlated color temperature in kelvins.
+///
+/// The distribution is a combination of the S0, S1, and S2 basis functions, tabulated from 380 nm
+/// to 780 nm in 10 nm steps, and is normalized to 100 at 560 nm. As in CIE 15:2004, the
+/// coefficients are rounded to three decimal places so that D65 and D50 reproduce the published
+/// tables.
+pub fn daylight_spd(cct: f64) -> Result<SpectralDistribution, Error> {
+    let v = daylight_chromaticity(cct)?.to_vector3();
+    let (x, y) = (v[0], v[1]);
+    let m = 0.0241 + 0.2562 * x - 0.7341 * y;
+    let round = |value: f64| (value * 1000.0).round() / 1000.0;
+    let m1 = round((-1.3515 - 1.7703 * x + 5.9114 * y) / m);
+    let m2 = round((0.0300 - 31.4424 * x + 30.0717 * y) / m);
+    let values = DAYLIGHT_BASIS
+        .iter()
+        .map(|[s0, s1, s2]| s0 + m1 * s1 + m2 * s2)
+        .collect();
+    SpectralDistribution::uniform(380.0, 10.0, values)
+}
+
+/// Generate the relative spectral power distribution of CIE standard illuminant D65, normalized to
+/// 100 at 560 nm.
+pub fn d65() -> SpectralDistribution {
+    daylight_spd(6500.0 * 1.4388 / 1.438).expect("D65 is on the daylight locus.")
+}
+
+/// The lowest temperature in kelvins accepted for blackbody radiators. Below about 36 K, the
+/// exponential in Planck's law overflows at 560 nm, where the distribution is normalized, which
+/// would make every value NaN.
+pub const BLACKBODY_MIN_TEMPERATURE: f64 = 100.0;
+
+/// Generate the relative spectral power distribution of a blackbody radiator at a temperature in
+/// kelvins, using Planck's law. The distribution is normalized to 1.0 at 560 nm.
+pub fn blackbody_spd(temperature: f64, observer: Observer) -> Result<SpectralDistribution, Error> {
+    check_temperature(temperature)?;
+    let reference = planck(560.0, temperature);
+    Ok(SpectralDistribution::from_fn(observer, |wavelength| {
+        planck(wavelength, temperature) / reference
+    }))
+}
+
+/// Compute the chromaticity of a blackbody radiator at a temperature in kelvins. The returned
+/// white point has luminance Y = 1.0.
+pub fn blackbody_chromaticity(temperature: f64, observer: Observer) -> Result<CIEXYY, Error> {
+    let ciexyz: CIEXYZ = blackbody_spd(temperature, observer)?.to_ciexyz(observer);
+    Ok(ciexyz.to_color())
+}
+
+/// The spectral radiant exitance of a blackbody, up to a constant factor, at a wavelength in
+/// nanometers.
+fn planck(wavelength: f64, temperature: f64) -> f64 {
+    let meters = wavelength * 1e-9;
+    1.0 / (meters.powi(5) * ((C2 / (meters * temperature)).exp_m1()))
+}
+
+fn check_temperature(temperature: f64) -> Result<(), Error> {
+    if temperature >= BLACKBODY_MIN_TEMPERATURE && temperature.is_finite() {
+        Ok(())
+    } else {
+        Err(ColorTemperatureError(format!(
+            "Temperature must be at least {} K, got {} K",
+            BLACKBODY_MIN_TEMPERATURE, temperature
+        )))
+    }
+}
+
+fn check_daylight_cct(cct: f64) -> Result<(), Error> {
+    if (DAYLIGHT_CCT_RANGE.0..=DAYLIGHT_CCT_RANGE.1).contains(&cct) {
+        Ok(())
+    } else {
+        Err(ColorTemperatureError(format!(
+            "The daylight locus is defined from {} K to {} K, got {} K",
+            DAYLIGHT_CCT_RANGE.0, DAYLIGHT_CCT_RANGE.1, cct
+        )))
+    }
+}
+
+/// The S0, S1, and S2 basis functions of CIE daylight from 380 nm to 780 nm in 10 nm steps, from
+/// CIE 15:2004.
+#[rustfmt::skip]
+const DAYLIGHT_BASIS: [[f64; 3]; 41] = [
+    [63.4, 38.5, 3.0],
+    [65.8, 35.0, 1.2],
+    [94.8, 43.4, -1.1],
+    [104.8, 46.3, -0.5],
+    [105.9, 43.9, -0.7],
+    [96.8, 37.1, -1.2],
+    [113.9, 36.7, -2.6],
+    [125.6, 35.9, -2.9],
+    [125.5, 32.6, -2.8],
+    [121.3, 27.9, -2.6],
+    [121.3, 24.3, -2.6],
+    [113.5, 20.1, -1.8],
+    [113.1, 16.2, -1.5],
+    [110.8, 13.2, -1.3],
+    [106.5, 8.6, -1.2],
+    [108.8, 6.1, -1.0],
+    [105.3, 4.2, -0.5],
+    [104.4, 1.9, -0.3],
+    [100.0, 0.0, 0.0],
+    [96.0, -1.6, 0.2],
+    [95.1, -3.5, 0.5],
+    [89.1, -3.5, 2.1],
+    [90.5, -5.8, 3.2],
+    [90.3, -7.2, 4.1],
+    [88.4, -8.6, 4.7],
+    [84.0, -9.5, 5.1],
+    [85.1, -10.9, 6.7],
+    [81.9, -10.7, 7.3],
+    [82.6, -12.0, 8.6],
+    [84.9, -14.0, 9.8],
+    [81.3, -13.6, 10.2],
+    [71.9, -12.0, 8.3],
+    [74.3, -13.3, 9.6],
+    [76.4, -12.9, 8.5],
+    [63.3, -10.6, 7.0],
+    [71.7, -11.6, 7.6],
+    [77.0, -12.2, 8.0],
+    [65.2, -10.2, 6.7],
+    [47.7, -7.8, 5.2],
+    [68.6, -11.2, 7.4],
+    [65.0, -10.4, 6.8],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::get_d65_ciexyz;
+
+    /// Illuminant A is defined as a blackbody at 2848 K with the older second radiation constant
+    /// of 1.435e-2 m K, or about 2856 K today.
+    const ILLUMINANT_A: f64 = 2848.0 * 1.4388 / 1.435;
+
+    fn chromaticity(xyy: &CIEXYY) -> (f64, f64) {
+        let v = xyy.to_vector3();
+        (v[0], v[1])
+    }
+
+    #[test]
+    fn test_daylight_chromaticity() -> Result<(), Error> {
+        // The published chromaticities of D65 and D50 are computed from their tabulated
+        // distributions, so they differ slightly from the daylight locus.
+        let (x, y) = chromaticity(&daylight_chromaticity(6500.0 * 1.4388 / 1.438)?);
+        assert!((x - 0.31271).abs() < 2e-4);
+        assert!((y - 0.32902).abs() < 2e-4);
+        let (x, y) = chromaticity(&daylight_chromaticity(5000.0 * 1.4388 / 1.438)?);
+        assert!((x - 0.34567).abs() < 2e-4);
+        assert!((y - 0.35850).abs() < 2e-4);
+        assert!(daylight_chromaticity(3000.0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_daylight_spd_matches_d65() -> Result<(), Error> {
+        let d65 = d65();
+        assert!((d65.value_at(380.0) - 49.9755).abs() < 0.02);
+        assert!((d65.value_at(460.0) - 117.812).abs() < 0.02);
+        assert!((d65.value_at(560.0) - 100.0).abs() < 1e-9);
+        assert!((d65.value_at(700.0) - 71.6091).abs() < 0.02);
+
+        let white = d65.to_ciexyz(Observer::CIE1931);
+        assert!((white.to_vector3() - get_d65_ciexyz().to_vector3()).amax() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_blackbody_chromaticity() -> Result<(), Error> {
+        let (x, y) = chromaticity(&blackbody_chromaticity(ILLUMINANT_A, Observer::CIE1931)?);
+        assert!((x - 0.44757).abs() < 1e-4);
+        assert!((y - 0.40745).abs() < 1e-4);
+        let (x, y) = chromaticity(&blackbody_chromaticity(ILLUMINANT_A, Observer::CIE1964)?);
+        assert!((x - 0.45117).abs() < 1e-4);
+        assert!((y - 0.40594).abs() < 1e-4);
+        assert!(blackbody_spd(0.0, Observer::CIE1931).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_low_temperature_blackbody() -> Result<(), Error> {
+        for observer in &[Observer::CIE1931, Observer::CIE1964] {
+            let spd = blackbody_spd(BLACKBODY_MIN_TEMPERATURE, *observer)?;
+            assert!(spd.values().iter().all(|value| value.is_finite()));
+            let (x, y) = chromaticity(&blackbody_chromaticity(
+                BLACKBODY_MIN_TEMPERATURE,
+                *observer,
+            )?);
+            assert!(x.is_finite() && y.is_finite());
+            assert!(blackbody_spd(50.0, *observer).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_blackbody_spd_normalization() -> Result<(), Error> {
+        let spd = blackbody_spd(ILLUMINANT_A, Observer::CIE1931)?;
+        assert!((spd.value_at(560.0) - 1.0).abs() < 1e-12);
+        // Illuminant A is tabulated as 100 at 560 nm and 9.7951 at 380 nm.
+        assert!((spd.value_at(380.0) * 100.0 - 9.7951).abs() < 1e-3);
+        Ok(())
+    }
+}
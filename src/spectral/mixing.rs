@@ -0,0 +1,182 @@
+use super::{d65, reflectance_from_srgb, Observer, SpectralDistribution, UpsamplingMethod};
+use crate::colors::{Color, SRGB};
+
+/// The smallest reflectance used when computing absorption to scattering ratios, which are
+/// infinite for a reflectance of zero.
+const MIN_REFLECTANCE: f64 = 1e-4;
+
+/// An opaque pigment, described by the ratio K/S of its absorption to its scattering at each
+/// wavelength, as in the single-constant Kubelka-Munk model.
+///
+/// Mixing pigments averages their K/S ratios rather than their reflectances, which is how a blue
+/// and a yellow paint make a green rather than a gray.
+///
+/// See Kubelka and Munk, "Ein Beitrag zur Optik der Farbanstriche" (1931), and Duncan, "The colour
+/// of pigment mixtures" (1940).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pigment {
+    absorption_scattering: SpectralDistribution,
+}
+
+impl Pigment {
+    /// Create a pigment from the reflectance of an opaque layer of it.
+    pub fn from_reflectance(reflectance: &SpectralDistribution) -> Self {
+        let ratios = reflectance
+            .values()
+            .iter()
+            .map(|r| {
+                let r = r.clamp(MIN_REFLECTANCE, 1.0);
+                (1.0 - r).powi(2) / (2.0 * r)
+            })
+            .collect();
+        Pigment {
+            absorption_scattering: SpectralDistribution::new(
+                reflectance.wavelengths().to_vec(),
+                ratios,
+            )
+            .expect("The wavelengths are taken from a valid distribution."),
+        }
+    }
+
+    /// Create a pigment whose reflectance has approximately the given color under D65, by
+    /// upsampling it to a spectrum.
+    pub fn from_color<C>(color: &C, method: UpsamplingMethod) -> Self
+    where
+        C: Color,
+    {
+        Self::from_reflectance(&reflectance_from_srgb(&color.to_color::<SRGB>(), method))
+    }
+
+    /// The ratio K/S of absorption to scattering at each wavelength.
+    pub fn absorption_scattering(&self) -> &SpectralDistribution {
+        &self.absorption_scattering
+    }
+
+    /// The reflectance of an opaque layer of the pigment.
+    pub fn reflectance(&self) -> SpectralDistribution {
+        let values = self
+            .absorption_scattering
+            .values()
+            .iter()
+            .map(|ks| 1.0 + ks - (ks * ks + 2.0 * ks).sqrt())
+            .collect();
+        SpectralDistribution::new(self.absorption_scattering.wavelengths().to_vec(), values)
+            .expect("The wavelengths are taken from a valid distribution.")
+    }
+
+    /// The color of an opaque layer of the pigment under D65.
+    pub fn to_color<C>(&self) -> C
+    where
+        C: Color,
+    {
+        self.reflectance()
+            .reflectance_to_ciexyz(&d65(), Observer::CIE1931)
+            .to_color()
+    }
+
+    /// Mix pigments in the given proportions. The weights are relative, and need not sum to 1.
+    ///
+    /// Returns `None` if there are no pigments, any weight is negative or NaN, or the weights sum
+    /// to zero.
+    pub fn mix(pigments: &[(&Pigment, f64)]) -> Option<Pigment> {
+        if pigments
+            .iter()
+            .any(|(_, weight)| weight.is_nan() || *weight < 0.0)
+        {
+            return None;
+        }
+        let total: f64 = pigments.iter().map(|(_, weight)| weight).sum();
+        if pigments.is_empty() || total <= 0.0 {
+            return None;
+        }
+        let mut wavelengths: Vec<f64> = pigments
+            .iter()
+            .flat_map(|(pigment, _)| pigment.absorption_scattering.wavelengths().iter().copied())
+            .collect();
+        wavelengths.sort_by(|a, b| a.partial_cmp(b).expect("Wavelength is not NaN."));
+        wavelengths.dedup();
+        let values = wavelengths
+            .iter()
+            .map(|wavelength| {
+                pigments
+                    .iter()
+                    .map(|(pigment, weight)| {
+                        weight * pigment.absorption_scattering.value_at(*wavelength)
+                    })
+                    .sum::<f64>()
+                    / total
+            })
+            .collect();
+        Some(Pigment {
+            absorption_scattering: SpectralDistribution::new(wavelengths, values)
+                .expect("The wavelengths are sorted and deduplicated."),
+        })
+    }
+}
+
+/// Mix colors as if they were paints, using the Kubelka-Munk model over spectra upsampled with
+/// the Jakob-Hanika method. The weights are relative, and need not sum to 1.
+///
+/// Returns `None` if there are no colors, any weight is negative or NaN, or the weights sum to
+/// zero.
+pub fn mix_colors<C>(colors: &[(C, f64)]) -> Option<C>
+where
+    C: Color,
+{
+    let pigments: Vec<(Pigment, f64)> = colors
+        .iter()
+        .map(|(color, weight)| {
+            (
+                Pigment::from_color(color, UpsamplingMethod::JakobHanika2019),
+                *weight,
+            )
+        })
+        .collect();
+    let references: Vec<(&Pigment, f64)> = pigments
+        .iter()
+        .map(|(pigment, weight)| (pigment, *weight))
+        .collect();
+    Pigment::mix(&references).map(|pigment| pigment.to_color())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::LchSpace;
+    use crate::difference::{delta_e, DeltaE};
+    use crate::errors::Error;
+
+    #[test]
+    fn test_blue_and_yellow_make_green() -> Result<(), Error> {
+        let blue = SRGB::from_hex("1f4fbf")?;
+        let yellow = SRGB::from_hex("f5d31f")?;
+        let green = mix_colors(&[(blue, 1.0), (yellow, 1.0)]).expect("The weights are positive.");
+        let (_, chroma, hue) = LchSpace::OKLCH.to_lch(&green);
+        assert!(chroma > 0.05, "{}", chroma);
+        let hue = hue.to_degrees().rem_euclid(360.0);
+        assert!((100.0..200.0).contains(&hue), "{}", hue);
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_pigment_round_trip() -> Result<(), Error> {
+        let color = SRGB::from_hex("bc3fbc")?;
+        let mixed = mix_colors(&[(color, 2.0)]).expect("The weight is positive.");
+        assert!(delta_e(color, mixed, DeltaE::CIEDE2000) < 0.1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_weights() -> Result<(), Error> {
+        let white = SRGB::from_hex("f0f0f0")?;
+        let red = SRGB::from_hex("cd3131")?;
+        let light = mix_colors(&[(white, 3.0), (red, 1.0)]).expect("The weights are positive.");
+        let dark = mix_colors(&[(white, 1.0), (red, 3.0)]).expect("The weights are positive.");
+        assert!(LchSpace::OKLCH.to_lch(&light).0 > LchSpace::OKLCH.to_lch(&dark).0);
+        assert!(mix_colors::<SRGB>(&[]).is_none());
+        assert!(mix_colors(&[(red, 0.0)]).is_none());
+        assert!(mix_colors(&[(white, 2.0), (red, -1.0)]).is_none());
+        assert!(mix_colors(&[(white, 1.0), (red, f64::NAN)]).is_none());
+        Ok(())
+    }
+}
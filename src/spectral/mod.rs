@@ -0,0 +1,18 @@
+mod cct;
+mod cmf;
+mod illuminants;
+mod mixing;
+mod spd;
+mod upsampling;
+mod wavelength;
+
+pub use cct::{cct_duv, chromaticity_from_cct_duv, CctMethod, OHNO_CCT_RANGE};
+pub use cmf::{Observer, WAVELENGTH_RANGE};
+pub use illuminants::{
+    blackbody_chromaticity, blackbody_spd, d65, daylight_chromaticity, daylight_spd,
+    BLACKBODY_MIN_TEMPERATURE, DAYLIGHT_CCT_RANGE,
+};
+pub use mixing::{mix_colors, Pigment};
+pub use spd::SpectralDistribution;
+pub use upsampling::{reflectance_from_srgb, SigmoidPolynomial, UpsamplingMethod};
+pub use wavelength::{dominant_wavelength, DominantWavelength, WavelengthKind};
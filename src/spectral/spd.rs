@@ -0,0 +1,261 @@
+use nalgebra::Vector3;
+
+use super::Observer;
+use crate::colors::{Color, CIEXYZ};
+use crate::errors::Error;
+use crate::errors::Error::SpectralDataError;
+
+/// A spectral distribution: a quantity sampled at increasing wavelengths, in nanometers.
+///
+/// This represents both the spectral power distributions of light sources and the spectral
+/// reflectances of surfaces. Values between samples are linearly interpolated. Values outside of
+/// the sampled range repeat the nearest sample, as recommended by CIE 15:2004.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectralDistribution {
+    wavelengths: Vec<f64>,
+    values: Vec<f64>,
+}
+
+impl SpectralDistribution {
+    /// Create a distribution from samples at arbitrary wavelengths, which must be finite and
+    /// strictly increasing.
+    pub fn new(wavelengths: Vec<f64>, values: Vec<f64>) -> Result<Self, Error> {
+        if wavelengths.is_empty() {
+            return Err(SpectralDataError(String::from(
+                "A spectral distribution needs at least one sample",
+            )));
+        }
+        if wavelengths.len() != values.len() {
+            return Err(SpectralDataError(format!(
+                "Got {} wavelengths but {} values",
+                wavelengths.len(),
+                values.len()
+            )));
+        }
+        if let Some(wavelength) = wavelengths.iter().find(|w| !w.is_finite()) {
+            return Err(SpectralDataError(format!(
+                "Wavelengths must be finite, got {}",
+                wavelength
+            )));
+        }
+        if let Some(pair) = wavelengths.windows(2).find(|pair| pair[0] >= pair[1]) {
+            return Err(SpectralDataError(format!(
+                "Wavelengths are not strictly increasing: {} is followed by {}",
+                pair[0], pair[1]
+            )));
+        }
+        Ok(SpectralDistribution {
+            wavelengths,
+            values,
+        })
+    }
+
+    /// Create a distribution from samples spaced evenly by `interval` nanometers, starting at
+    /// `start`.
+    pub fn uniform(start: f64, interval: f64, values: Vec<f64>) -> Result<Self, Error> {
+        let wavelengths = (0..values.len())
+            .map(|i| start + i as f64 * interval)
+            .collect();
+        Self::new(wavelengths, values)
+    }
+
+    /// Create a distribution by evaluating a function at the wavelengths of an observer's color
+    /// matching functions.
+    pub fn from_fn<F>(observer: Observer, f: F) -> Self
+    where
+        F: Fn(f64) -> f64,
+    {
+        let wavelengths: Vec<f64> = observer.wavelengths().collect();
+        let values = wavelengths
+            .iter()
+            .map(|wavelength| f(*wavelength))
+            .collect();
+        SpectralDistribution {
+            wavelengths,
+            values,
+        }
+    }
+
+    pub fn wavelengths(&self) -> &[f64] {
+        &self.wavelengths
+    }
+
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// The value of the distribution at a wavelength in nanometers, or NaN if the wavelength is
+    /// NaN.
+    pub fn value_at(&self, wavelength: f64) -> f64 {
+        if wavelength.is_nan() {
+            return f64::NAN;
+        }
+        let n = self.wavelengths.len();
+        if wavelength <= self.wavelengths[0] {
+            return self.values[0];
+        }
+        if wavelength >= self.wavelengths[n - 1] {
+            return self.values[n - 1];
+        }
+        let upper = self.wavelengths.partition_point(|w| *w <= wavelength);
+        let lower = upper - 1;
+        let t = (wavelength - self.wavelengths[lower])
+            / (self.wavelengths[upper] - self.wavelengths[lower]);
+        self.values[lower] * (1.0 - t) + self.values[upper] * t
+    }
+
+    /// Compute the color of a light source with this spectral power distribution, normalized so
+    /// that its luminance is Y = 1.0.
+    ///
+    /// A distribution with no luminance at all is black.
+    pub fn to_ciexyz(&self, observer: Observer) -> CIEXYZ {
+        let tristimulus = self.integrate(observer);
+        if tristimulus[1] == 0.0 {
+            return CIEXYZ::from_vector3(tristimulus);
+        }
+        CIEXYZ::from_vector3(tristimulus / tristimulus[1])
+    }
+
+    /// Compute the color of a surface with this spectral reflectance, lit by an illuminant with the
+    /// given spectral power distribution.
+    ///
+    /// The result is normalized so that the perfect reflecting diffuser, with a reflectance of 1.0
+    /// at every wavelength, has Y = 1.0. Every surface is black under an illuminant with no
+    /// luminance at all.
+    pub fn reflectance_to_ciexyz(
+        &self,
+        illuminant: &SpectralDistribution,
+        observer: Observer,
+    ) -> CIEXYZ {
+        let white = illuminant.integrate(observer);
+        if white[1] == 0.0 {
+            return CIEXYZ::from_vector3(Vector3::zeros());
+        }
+        let reflected = self.product(illuminant).integrate(observer);
+        CIEXYZ::from_vector3(reflected / white[1])
+    }
+
+    /// The wavelength-by-wavelength product of two distributions, sampled at the wavelengths of
+    /// both.
+    pub fn product(&self, other: &SpectralDistribution) -> SpectralDistribution {
+        let mut wavelengths: Vec<f64> = self
+            .wavelengths
+            .iter()
+            .chain(other.wavelengths.iter())
+            .copied()
+            .collect();
+        wavelengths.sort_by(|a, b| a.partial_cmp(b).expect("Wavelength is not NaN."));
+        wavelengths.dedup();
+        let values = wavelengths
+            .iter()
+            .map(|wavelength| self.value_at(*wavelength) * other.value_at(*wavelength))
+            .collect();
+        SpectralDistribution {
+            wavelengths,
+            values,
+        }
+    }
+
+    /// The unnormalized X, Y, and Z tristimulus values, summed at the wavelengths of the
+    /// observer's color matching functions.
+    fn integrate(&self, observer: Observer) -> Vector3<f64> {
+        observer
+            .wavelengths()
+            .map(|wavelength| {
+                observer.color_matching_functions(wavelength) * self.value_at(wavelength)
+            })
+            .sum::<Vector3<f64>>()
+            * observer.interval()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{get_d65_ciexyz, CIEXYY};
+
+    /// The relative spectral power distribution of CIE standard illuminant D65 from 380 nm to
+    /// 780 nm in 10 nm steps.
+    fn d65() -> Result<SpectralDistribution, Error> {
+        SpectralDistribution::uniform(
+            380.0,
+            10.0,
+            vec![
+                49.9755, 54.6482, 82.7549, 91.486, 93.4318, 86.6823, 104.865, 117.008, 117.812,
+                114.861, 115.923, 108.811, 109.354, 107.802, 104.790, 107.689, 104.405, 104.046,
+                100.000, 96.3342, 95.788, 88.6856, 90.0062, 89.5991, 87.6987, 83.2886, 83.6992,
+                80.0268, 80.2146, 82.2778, 78.2842, 69.7213, 71.6091, 74.349, 61.604, 69.8856,
+                75.087, 63.5927, 46.4182, 66.8054, 63.3828,
+            ],
+        )
+    }
+
+    fn chromaticity(ciexyz: &CIEXYZ) -> (f64, f64) {
+        let xyy: CIEXYY = ciexyz.to_color();
+        let v = xyy.to_vector3();
+        (v[0], v[1])
+    }
+
+    #[test]
+    fn test_new_validation() {
+        assert!(SpectralDistribution::new(vec![], vec![]).is_err());
+        assert!(SpectralDistribution::new(vec![400.0, 500.0], vec![1.0]).is_err());
+        assert!(SpectralDistribution::new(vec![500.0, 400.0], vec![1.0, 1.0]).is_err());
+        assert!(SpectralDistribution::new(vec![400.0, f64::NAN], vec![1.0, 1.0]).is_err());
+        assert!(SpectralDistribution::new(vec![f64::NAN], vec![1.0]).is_err());
+        assert!(SpectralDistribution::new(vec![400.0, f64::INFINITY], vec![1.0, 1.0]).is_err());
+        assert!(SpectralDistribution::new(vec![400.0, 500.0], vec![1.0, 1.0]).is_ok());
+    }
+
+    #[test]
+    fn test_value_at() -> Result<(), Error> {
+        let spd = SpectralDistribution::new(vec![400.0, 500.0, 700.0], vec![1.0, 3.0, 2.0])?;
+        assert_eq!(spd.value_at(450.0), 2.0);
+        assert_eq!(spd.value_at(600.0), 2.5);
+        assert_eq!(spd.value_at(500.0), 3.0);
+        assert_eq!(spd.value_at(300.0), 1.0);
+        assert_eq!(spd.value_at(800.0), 2.0);
+        assert!(spd.value_at(f64::NAN).is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn test_equal_energy_white() {
+        for observer in &[Observer::CIE1931, Observer::CIE1964] {
+            let white = SpectralDistribution::from_fn(*observer, |_| 1.0);
+            let (x, y) = chromaticity(&white.to_ciexyz(*observer));
+            assert!((x - 1.0 / 3.0).abs() < 1e-4);
+            assert!((y - 1.0 / 3.0).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_d65_white_point() -> Result<(), Error> {
+        let d65 = d65()?;
+        let (x, y) = chromaticity(&d65.to_ciexyz(Observer::CIE1931));
+        assert!((x - 0.3127).abs() < 1e-4);
+        assert!((y - 0.3290).abs() < 1e-4);
+        let (x, y) = chromaticity(&d65.to_ciexyz(Observer::CIE1964));
+        assert!((x - 0.3138).abs() < 1e-4);
+        assert!((y - 0.3310).abs() < 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reflectance() -> Result<(), Error> {
+        let d65 = d65()?;
+        let perfect = SpectralDistribution::uniform(380.0, 400.0, vec![1.0, 1.0])?;
+        let white = perfect.reflectance_to_ciexyz(&d65, Observer::CIE1931);
+        let expected = get_d65_ciexyz();
+        assert!((white.to_vector3() - expected.to_vector3()).amax() < 1e-3);
+
+        let gray = SpectralDistribution::uniform(380.0, 400.0, vec![0.2, 0.2])?;
+        let gray = gray.reflectance_to_ciexyz(&d65, Observer::CIE1931);
+        assert!((gray.to_vector3() - expected.to_vector3() * 0.2).amax() < 1e-3);
+
+        let dark = SpectralDistribution::uniform(380.0, 400.0, vec![0.0, 0.0])?;
+        let black = perfect.reflectance_to_ciexyz(&dark, Observer::CIE1931);
+        assert_eq!(black.to_vector3(), Vector3::zeros());
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use nalgebra::{Matrix3, Vector3};
+
+use super::{d65, Observer, SpectralDistribution, WAVELENGTH_RANGE};
+use crate::colors::{Color, CIELAB, SRGB};
+
+/// The maximum number of Levenberg-Marquardt iterations used to fit a sigmoid polynomial.
+const FIT_ITERATIONS: usize = 100;
+
+/// Fitting stops once the color difference from the target, in CIELAB units, is below this.
+const FIT_TOLERANCE: f64 = 1e-4;
+
+/// A method of computing a plausible reflectance spectrum for an RGB color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpsamplingMethod {
+    /// Smits, "An RGB-to-Spectrum Conversion for Reflectances" (1999). A combination of seven
+    /// piecewise-constant basis spectra. Fast, but only approximately reproduces the input color.
+    Smits1999,
+    /// Jakob and Hanika, "A Low-Dimensional Function Space for Efficient Spectral Upsampling"
+    /// (2019). A smooth sigmoid of a quadratic polynomial, fitted to reproduce the input color
+    /// under D65.
+    JakobHanika2019,
+}
+
+/// Compute a reflectance spectrum that has approximately the given sRGB color under D65.
+///
+/// Components outside of [0, 1] are clamped, since a reflectance can neither be negative nor
+/// reflect more light than it receives.
+pub fn reflectance_from_srgb(color: &SRGB, method: UpsamplingMethod) -> SpectralDistribution {
+    match method {
+        UpsamplingMethod::Smits1999 => smits(color),
+        UpsamplingMethod::JakobHanika2019 => SigmoidPolynomial::fit(color).to_spd(),
+    }
+}
+
+/// A reflectance spectrum of the form sigmoid(c₀t² + c₁t + c₂), where t is the wavelength mapped
+/// from `WAVELENGTH_RANGE` to [0, 1], and sigmoid(x) = 1/2 + x / (2√(1 + x²)).
+///
+/// Any such spectrum is smooth and lies strictly between 0 and 1, and three coefficients are
+/// enough to match any color inside the sRGB gamut.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SigmoidPolynomial {
+    pub coefficients: [f64; 3],
+}
+
+impl SigmoidPolynomial {
+    /// Fit the coefficients so that the spectrum has the given sRGB color under D65, using the
+    /// Levenberg-Marquardt algorithm to minimize the color difference in CIELAB.
+    ///
+    /// Colors with components of exactly 0 or 1 can only be approached, not reached, so their
+    /// fits are as close as the iteration limit allows.
+    pub fn fit(color: &SRGB) -> Self {
+        let illuminant = d65();
+        let target = clamp_srgb(color).to_color::<CIELAB>().to_vector3() * 100.0;
+        let residual = |coefficients: Vector3<f64>| -> Vector3<f64> {
+            let spd = SigmoidPolynomial {
+                coefficients: coefficients.into(),
+            }
+            .to_spd();
+            let lab: CIELAB = spd
+                .reflectance_to_ciexyz(&illuminant, Observer::CIE1931)
+                .to_color();
+            lab.to_vector3() * 100.0 - target
+        };
+
+        let mut coefficients = Vector3::zeros();
+        let mut r = residual(coefficients);
+        let mut damping = 1e-3;
+        for _ in 0..FIT_ITERATIONS {
+            if r.norm() < FIT_TOLERANCE {
+                break;
+            }
+            let mut jacobian = Matrix3::zeros();
+            for i in 0..3 {
+                let mut step = Vector3::zeros();
+                step[i] = 1e-6;
+                jacobian.set_column(i, &((residual(coefficients + step) - r) / 1e-6));
+            }
+            let gradient = jacobian.transpose() * r;
+            let hessian = jacobian.transpose() * jacobian;
+            let damped = hessian + Matrix3::from_diagonal(&hessian.diagonal()) * damping;
+            let delta = match damped.try_inverse() {
+                Some(inverse) => -(inverse * gradient),
+                None => break,
+            };
+            let candidate = coefficients + delta;
+            let candidate_r = residual(candidate);
+            if candidate_r.norm() < r.norm() {
+                coefficients = candidate;
+                r = candidate_r;
+                damping /= 10.0;
+            } else {
+                damping *= 10.0;
+            }
+        }
+        SigmoidPolynomial {
+            coefficients: coefficients.into(),
+        }
+    }
+
+    /// The reflectance at a wavelength in nanometers.
+    pub fn value_at(&self, wavelength: f64) -> f64 {
+        let t = (wavelength - WAVELENGTH_RANGE.0) / (WAVELENGTH_RANGE.1 - WAVELENGTH_RANGE.0);
+        let [c0, c1, c2] = self.coefficients;
+        let x = (c0 * t + c1) * t + c2;
+        0.5 + x / (2.0 * (1.0 + x * x).sqrt())
+    }
+
+    /// Sample the spectrum at the wavelengths of the CIE 1931 color matching functions.
+    pub fn to_spd(&self) -> SpectralDistribution {
+        SpectralDistribution::from_fn(Observer::CIE1931, |wavelength| self.value_at(wavelength))
+    }
+}
+
+fn smits(color: &SRGB) -> SpectralDistribution {
+    let linear = clamp_srgb(color).to_linear();
+    let (r, g, b) = (linear[0], linear[1], linear[2]);
+    let mut values = [0.0; 10];
+    let mut add = |weight: f64, basis: &[f64; 10]| {
+        for (value, basis) in values.iter_mut().zip(basis.iter()) {
+            *value += weight * basis;
+        }
+    };
+    if r <= g && r <= b {
+        add(r, &SMITS_WHITE);
+        if g <= b {
+            add(g - r, &SMITS_CYAN);
+            add(b - g, &SMITS_BLUE);
+        } else {
+            add(b - r, &SMITS_CYAN);
+            add(g - b, &SMITS_GREEN);
+        }
+    } else if g <= r && g <= b {
+        add(g, &SMITS_WHITE);
+        if r <= b {
+            add(r - g, &SMITS_MAGENTA);
+            add(b - r, &SMITS_BLUE);
+        } else {
+            add(b - g, &SMITS_MAGENTA);
+            add(r - b, &SMITS_RED);
+        }
+    } else {
+        add(b, &SMITS_WHITE);
+        if r <= g {
+            add(r - b, &SMITS_YELLOW);
+            add(g - r, &SMITS_GREEN);
+        } else {
+            add(g - b, &SMITS_YELLOW);
+            add(r - g, &SMITS_RED);
+        }
+    }
+    SpectralDistribution::uniform(
+        WAVELENGTH_RANGE.0 + SMITS_BIN_WIDTH / 2.0,
+        SMITS_BIN_WIDTH,
+        values.to_vec(),
+    )
+    .expect("The Smits bins are increasing.")
+}
+
+fn clamp_srgb(color: &SRGB) -> SRGB {
+    SRGB::from_vector3(color.to_vector3().map(|c| c.clamp(0.0, 1.0)))
+}
+
+/// The width of each of Smits's ten bins, which span 380 nm to 720 nm. Each basis spectrum is
+/// sampled at the centers of the bins.
+const SMITS_BIN_WIDTH: f64 = 34.0;
+
+const SMITS_WHITE: [f64; 10] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const SMITS_CYAN: [f64; 10] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const SMITS_MAGENTA: [f64; 10] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const SMITS_YELLOW: [f64; 10] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [f64; 10] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [f64; 10] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const SMITS_BLUE: [f64; 10] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difference::{delta_e, DeltaE};
+    use crate::errors::Error;
+
+    const COLORS: [&str; 8] = [
+        "808080", "cd3131", "0dbc79", "2472c8", "e5e510", "bc3fbc", "f5deb3", "203040",
+    ];
+
+    fn round_trip(color: &SRGB, method: UpsamplingMethod) -> SRGB {
+        reflectance_from_srgb(color, method)
+            .reflectance_to_ciexyz(&d65(), Observer::CIE1931)
+            .to_color()
+    }
+
+    #[test]
+    fn test_jakob_hanika_round_trip() -> Result<(), Error> {
+        for hex in COLORS.iter() {
+            let color = SRGB::from_hex(hex)?;
+            let result = round_trip(&color, UpsamplingMethod::JakobHanika2019);
+            let difference = delta_e(color, result, DeltaE::CIEDE2000);
+            assert!(difference < 0.1, "{}: {}", hex, difference);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_jakob_hanika_is_a_reflectance() -> Result<(), Error> {
+        let fit = SigmoidPolynomial::fit(&SRGB::from_hex("0dbc79")?);
+        for wavelength in Observer::CIE1931.wavelengths() {
+            let value = fit.value_at(wavelength);
+            assert!(value > 0.0 && value < 1.0);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_smits_round_trip() -> Result<(), Error> {
+        for hex in COLORS.iter() {
+            let color = SRGB::from_hex(hex)?;
+            let result = round_trip(&color, UpsamplingMethod::Smits1999);
+            let difference = delta_e(color, result, DeltaE::CIEDE2000);
+            assert!(difference < 3.0, "{}: {}", hex, difference);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_smits_white_and_black() -> Result<(), Error> {
+        let white = reflectance_from_srgb(&SRGB::from_hex("fff")?, UpsamplingMethod::Smits1999);
+        assert!(white.values().iter().all(|v| (v - 1.0).abs() < 1e-3));
+        let black = reflectance_from_srgb(&SRGB::from_hex("000")?, UpsamplingMethod::Smits1999);
+        assert!(black.values().iter().all(|v| *v == 0.0));
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use nalgebra::Vector2;
+
+use super::Observer;
+use crate::colors::{Color, CIEXYY};
+
+/// Whether a wavelength describing a color is its dominant or complementary wavelength.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WavelengthKind {
+    /// The color is a mixture of the white point and light of this wavelength.
+    Dominant,
+    /// The color is a purple, and mixing it with light of this wavelength gives the white point.
+    Complementary,
+}
+
+/// A description of a color by a wavelength and purity relative to a white point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DominantWavelength {
+    /// The wavelength in nanometers.
+    pub wavelength: f64,
+    pub kind: WavelengthKind,
+    /// The distance of the color from the white point, as a fraction of the distance from the
+    /// white point to the edge of the chromaticity diagram in the same direction.
+    pub excitation_purity: f64,
+    /// The fraction of the luminance of the color contributed by the spectral (or, for purples,
+    /// the purple boundary) stimulus when the color is matched by mixing it with the white point.
+    pub colorimetric_purity: f64,
+}
+
+/// Describe a color by its dominant or complementary wavelength and its purity, relative
+/// to a white point.
+///
+/// The line from the white point through the color is intersected with the spectral locus of
+/// the CIE 1931 2° observer, from 380 nm to 780 nm. If the line instead meets the purple
+/// boundary joining both ends of the locus, the color has no dominant wavelength, and the
+/// complementary wavelength is found by extending the line in the opposite direction. Returns
+/// `None` if the color has the same chromaticity as the white point.
+pub fn dominant_wavelength(color: &CIEXYY, white: &CIEXYY) -> Option<DominantWavelength> {
+    let white = white.to_vector3().xy();
+    let color = color.to_vector3().xy();
+    let direction = color - white;
+    if direction.norm() == 0.0 {
+        return None;
+    }
+
+    let observer = Observer::CIE1931;
+    let locus: Vec<(f64, Vector2<f64>)> = observer
+        .wavelengths()
+        .map(|wavelength| {
+            let v = observer.color_matching_functions(wavelength);
+            (wavelength, v.xy() / v.sum())
+        })
+        .collect();
+    let first = locus[0];
+    let last = locus[locus.len() - 1];
+
+    let intersect_locus = |direction: Vector2<f64>| {
+        locus
+            .windows(2)
+            .filter_map(|pair| {
+                let (t, s) = intersect_ray(white, direction, pair[0].1, pair[1].1)?;
+                let wavelength = pair[0].0 + (pair[1].0 - pair[0].0) * s;
+                Some((t, wavelength, pair[0].1 + (pair[1].1 - pair[0].1) * s))
+            })
+            .min_by(|(t1, _, _), (t2, _, _)| t1.partial_cmp(t2).expect("Distance is not NaN."))
+    };
+
+    let (kind, wavelength, boundary) = match intersect_locus(direction) {
+        Some((_, wavelength, point)) => (WavelengthKind::Dominant, wavelength, point),
+        None => {
+            let (_, wavelength, _) = intersect_locus(-direction)?;
+            let (t, _) = intersect_ray(white, direction, first.1, last.1)?;
+            (
+                WavelengthKind::Complementary,
+                wavelength,
+                white + direction * t,
+            )
+        }
+    };
+    let excitation_purity = direction.norm() / (boundary - white).norm();
+    Some(DominantWavelength {
+        wavelength,
+        kind,
+        excitation_purity,
+        colorimetric_purity: excitation_purity * boundary[1] / color[1],
+    })
+}
+
+/// Intersect the ray `origin + t * direction` for t > 0 with the segment from `start` to `end`,
+/// returning t and the fraction s of the way along the segment.
+fn intersect_ray(
+    origin: Vector2<f64>,
+    direction: Vector2<f64>,
+    start: Vector2<f64>,
+    end: Vector2<f64>,
+) -> Option<(f64, f64)> {
+    let edge = end - start;
+    let cross = |a: Vector2<f64>, b: Vector2<f64>| a[0] * b[1] - a[1] * b[0];
+    let denominator = cross(direction, edge);
+    if denominator == 0.0 {
+        return None;
+    }
+    let offset = start - origin;
+    let t = cross(offset, edge) / denominator;
+    let s = cross(offset, direction) / denominator;
+    // Allow for rounding when the ray passes through a vertex shared by two segments.
+    if t > 0.0 && (-1e-9..=1.0 + 1e-9).contains(&s) {
+        Some((t, s.clamp(0.0, 1.0)))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::Vector3;
+
+    use super::*;
+
+    fn xyy(x: f64, y: f64) -> CIEXYY {
+        CIEXYY::from_vector3(Vector3::new(x, y, 1.0))
+    }
+
+    fn d65() -> CIEXYY {
+        xyy(0.3127, 0.3290)
+    }
+
+    #[test]
+    fn test_dominant_wavelength() {
+        let result = dominant_wavelength(&xyy(0.54369557, 0.32107944), &d65())
+            .expect("The color is not white.");
+        assert_eq!(result.kind, WavelengthKind::Dominant);
+        assert!((result.wavelength - 616.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_complementary_wavelength() {
+        let result = dominant_wavelength(&xyy(0.37605506, 0.24452225), &d65())
+            .expect("The color is not white.");
+        assert_eq!(result.kind, WavelengthKind::Complementary);
+        assert!((result.wavelength - 509.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_purity() {
+        let observer = Observer::CIE1931;
+        let spectral = observer.color_matching_functions(550.0);
+        let spectral = xyy(spectral[0] / spectral.sum(), spectral[1] / spectral.sum());
+        let result = dominant_wavelength(&spectral, &d65()).expect("The color is not white.");
+        assert!((result.wavelength - 550.0).abs() < 1e-6);
+        assert!((result.excitation_purity - 1.0).abs() < 1e-9);
+        assert!((result.colorimetric_purity - 1.0).abs() < 1e-9);
+
+        let halfway = (spectral.to_vector3() + d65().to_vector3()) / 2.0;
+        let result = dominant_wavelength(&CIEXYY::from_vector3(halfway), &d65())
+            .expect("The color is not white.");
+        assert!((result.wavelength - 550.0).abs() < 1e-6);
+        assert!((result.excitation_purity - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_white_has_no_dominant_wavelength() {
+        assert_eq!(dominant_wavelength(&d65(), &d65()), None);
+    }
+}
@@ -0,0 +1,11 @@
+//! Helpers for displaying colors in terminal emulators.
+mod osc;
+mod xterm;
+
+pub use osc::{
+    apply_scheme_sequence, format_rgb_spec, osc_query_color, osc_reset_color, osc_set_color,
+    parse_osc_replies, parse_rgb_spec, reset_scheme_sequence, OscColorReply, OscTarget,
+};
+pub use xterm::{
+    ansi_16_palette, nearest_index, sgr_sequence, xterm_256_palette, ColorMode, Layer, SGR_RESET,
+};
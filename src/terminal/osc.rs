@@ -0,0 +1,304 @@
+use nalgebra::Vector3;
+
+use crate::colors::{Color, SRGB};
+use crate::encodings::to_bytes;
+use crate::errors::Error;
+use crate::errors::Error::OscParseError;
+use crate::schemes::ColorScheme;
+
+const OSC: &str = "\x1b]";
+const ST: &str = "\x1b\\";
+const BEL: u8 = 0x07;
+const ESC: u8 = 0x1b;
+
+/// A dynamic color of the terminal that can be set, queried, or reset with an OSC (Operating
+/// System Command) sequence.
+///
+/// See https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands for
+/// more information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OscTarget {
+    /// An entry of the 256-color palette (OSC 4).
+    Palette(u8),
+    /// The default foreground color (OSC 10).
+    Foreground,
+    /// The default background color (OSC 11).
+    Background,
+    /// The text cursor color (OSC 12).
+    Cursor,
+}
+
+/// A color reported by the terminal in response to an OSC query.
+#[derive(Clone, Copy, Debug)]
+pub struct OscColorReply {
+    pub target: OscTarget,
+    pub color: SRGB,
+}
+
+fn set_prefix(target: OscTarget) -> String {
+    match target {
+        OscTarget::Palette(index) => format!("4;{}", index),
+        OscTarget::Foreground => "10".to_string(),
+        OscTarget::Background => "11".to_string(),
+        OscTarget::Cursor => "12".to_string(),
+    }
+}
+
+/// Format a color as an X11 `rgb:rr/gg/bb` color specification.
+pub fn format_rgb_spec(color: &SRGB) -> String {
+    let [r, g, b] = to_bytes(color.to_vector3());
+    format!("rgb:{:0>2x}/{:0>2x}/{:0>2x}", r, g, b)
+}
+
+/// Parse an X11 `rgb:r/g/b` color specification, as sent by terminals in OSC replies.
+///
+/// Each component may have between one and four hexadecimal digits, and is scaled by the largest
+/// value representable with that many digits.
+pub fn parse_rgb_spec(spec: &str) -> Result<SRGB, Error> {
+    let components = spec
+        .strip_prefix("rgb:")
+        .ok_or_else(|| OscParseError(format!("Unsupported color specification: {:?}", spec)))?;
+    let parsed: Vec<f64> = components
+        .split('/')
+        .map(parse_rgb_component)
+        .collect::<Result<_, _>>()?;
+    if parsed.len() != 3 {
+        return Err(OscParseError(format!(
+            "An rgb: color specification must have 3 components, not {}",
+            parsed.len()
+        )));
+    }
+    Ok(SRGB::from_vector3(Vector3::new(
+        parsed[0], parsed[1], parsed[2],
+    )))
+}
+
+fn parse_rgb_component(component: &str) -> Result<f64, Error> {
+    if component.is_empty() || component.len() > 4 {
+        return Err(OscParseError(format!(
+            "An rgb: component must be between 1 and 4 digits long, not {}",
+            component.len()
+        )));
+    }
+    let value = u32::from_str_radix(component, 16).map_err(|_| {
+        OscParseError("An rgb: component may only contain hexadecimal digits".to_string())
+    })?;
+    let max = (1u32 << (4 * component.len())) - 1;
+    Ok(value as f64 / max as f64)
+}
+
+/// Build the OSC sequence that sets a dynamic color of the terminal.
+pub fn osc_set_color(target: OscTarget, color: &SRGB) -> String {
+    format!(
+        "{}{};{}{}",
+        OSC,
+        set_prefix(target),
+        format_rgb_spec(color),
+        ST
+    )
+}
+
+/// Build the OSC sequence that asks the terminal to report a dynamic color.
+pub fn osc_query_color(target: OscTarget) -> String {
+    format!("{}{};?{}", OSC, set_prefix(target), ST)
+}
+
+/// Build the OSC sequence that resets a dynamic color to the terminal's configured default.
+pub fn osc_reset_color(target: OscTarget) -> String {
+    let command = match target {
+        OscTarget::Palette(index) => format!("104;{}", index),
+        OscTarget::Foreground => "110".to_string(),
+        OscTarget::Background => "111".to_string(),
+        OscTarget::Cursor => "112".to_string(),
+    };
+    format!("{}{}{}", OSC, command, ST)
+}
+
+/// Build the OSC sequences that apply a color scheme to the running terminal.
+pub fn apply_scheme_sequence(scheme: &ColorScheme) -> String {
+    let mut sequence = String::new();
+    for (index, color) in scheme.ansi.iter().enumerate() {
+        sequence.push_str(&osc_set_color(OscTarget::Palette(index as u8), color));
+    }
+    sequence.push_str(&osc_set_color(OscTarget::Foreground, &scheme.foreground));
+    sequence.push_str(&osc_set_color(OscTarget::Background, &scheme.background));
+    sequence.push_str(&osc_set_color(OscTarget::Cursor, &scheme.cursor));
+    sequence
+}
+
+/// Build the OSC sequences that undo `apply_scheme_sequence`.
+pub fn reset_scheme_sequence() -> String {
+    let mut sequence = format!("{}104{}", OSC, ST);
+    sequence.push_str(&osc_reset_color(OscTarget::Foreground));
+    sequence.push_str(&osc_reset_color(OscTarget::Background));
+    sequence.push_str(&osc_reset_color(OscTarget::Cursor));
+    sequence
+}
+
+/// Extract every color reply from a stream of bytes read from the terminal.
+///
+/// Bytes outside of OSC sequences, such as other terminal responses or user input, are ignored, as
+/// are OSC sequences that do not report colors and colors in formats other than `rgb:`, such as
+/// `rgba:`. Malformed `rgb:` colors are errors. Sequences may be terminated by either BEL or ST.
+pub fn parse_osc_replies(bytes: &[u8]) -> Result<Vec<OscColorReply>, Error> {
+    let mut replies = Vec::new();
+    let mut position = 0;
+    while let Some(offset) = find_osc_start(&bytes[position..]) {
+        let body_start = position + offset + 2;
+        let (body_end, next) = find_terminator(bytes, body_start).ok_or_else(|| {
+            OscParseError("Unterminated OSC sequence at end of input".to_string())
+        })?;
+        let body = std::str::from_utf8(&bytes[body_start..body_end])
+            .map_err(|_| OscParseError("An OSC sequence must be valid UTF-8".to_string()))?;
+        parse_osc_body(body, &mut replies)?;
+        position = next;
+    }
+    Ok(replies)
+}
+
+fn find_osc_start(bytes: &[u8]) -> Option<usize> {
+    bytes.windows(2).position(|w| w == [ESC, b']'])
+}
+
+/// Returns the end of the OSC body and the position just after its terminator.
+fn find_terminator(bytes: &[u8], start: usize) -> Option<(usize, usize)> {
+    (start..bytes.len()).find_map(|i| match bytes[i] {
+        BEL => Some((i, i + 1)),
+        ESC if bytes.get(i + 1) == Some(&b'\\') => Some((i, i + 2)),
+        _ => None,
+    })
+}
+
+/// Parse the color of a reply, or `None` if it is in a format other than `rgb:`.
+fn parse_reply_color(spec: &str) -> Result<Option<SRGB>, Error> {
+    if spec.starts_with("rgb:") {
+        parse_rgb_spec(spec).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn parse_osc_body(body: &str, replies: &mut Vec<OscColorReply>) -> Result<(), Error> {
+    let mut fields = body.split(';');
+    let command = fields.next().unwrap_or("");
+    match command {
+        "4" => {
+            let rest: Vec<&str> = fields.collect();
+            if rest.len() % 2 == 1 {
+                return Err(OscParseError(
+                    "OSC 4 must contain index and color pairs".to_string(),
+                ));
+            }
+            for pair in rest.chunks(2) {
+                let index = pair[0]
+                    .parse::<u8>()
+                    .map_err(|_| OscParseError(format!("Invalid palette index: {:?}", pair[0])))?;
+                if let Some(color) = parse_reply_color(pair[1])? {
+                    replies.push(OscColorReply {
+                        target: OscTarget::Palette(index),
+                        color,
+                    });
+                }
+            }
+        }
+        "10" | "11" | "12" => {
+            let target = match command {
+                "10" => OscTarget::Foreground,
+                "11" => OscTarget::Background,
+                _ => OscTarget::Cursor,
+            };
+            let spec = fields
+                .next()
+                .ok_or_else(|| OscParseError(format!("OSC {} is missing a color", command)))?;
+            if let Some(color) = parse_reply_color(spec)? {
+                replies.push(OscColorReply { target, color });
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rgb_spec_digit_widths() -> Result<(), Error> {
+        assert_eq!(parse_rgb_spec("rgb:f/0/8")?.to_hex()?, "ff0088");
+        assert_eq!(parse_rgb_spec("rgb:ff/00/80")?.to_hex()?, "ff0080");
+        assert_eq!(parse_rgb_spec("rgb:ffff/0000/8080")?.to_hex()?, "ff0080");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rgb_spec_invalid() {
+        assert!(parse_rgb_spec("#ff0080").is_err());
+        assert!(parse_rgb_spec("rgb:ff/00").is_err());
+        assert!(parse_rgb_spec("rgb:fffff/0/0").is_err());
+        assert!(parse_rgb_spec("rgb:gg/00/00").is_err());
+    }
+
+    #[test]
+    fn test_osc_set_color() -> Result<(), Error> {
+        let color = SRGB::from_hex("1d2021")?;
+        assert_eq!(
+            osc_set_color(OscTarget::Palette(3), &color),
+            "\x1b]4;3;rgb:1d/20/21\x1b\\"
+        );
+        assert_eq!(
+            osc_set_color(OscTarget::Background, &color),
+            "\x1b]11;rgb:1d/20/21\x1b\\"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_scheme_sequence() {
+        assert_eq!(
+            reset_scheme_sequence(),
+            "\x1b]104\x1b\\\x1b]110\x1b\\\x1b]111\x1b\\\x1b]112\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_parse_recorded_replies() -> Result<(), Error> {
+        // Replies recorded from xterm after sending OSC 4;1;? and OSC 11;?, with a cursor
+        // position report and keyboard input interleaved.
+        let recorded =
+            b"\x1b]4;1;rgb:cdcd/0000/0000\x07\x1b[12;1Rq\x1b]11;rgb:1d1d/2020/2121\x1b\\";
+        let replies = parse_osc_replies(recorded)?;
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].target, OscTarget::Palette(1));
+        assert_eq!(replies[0].color.to_hex()?, "cd0000");
+        assert_eq!(replies[1].target, OscTarget::Background);
+        assert_eq!(replies[1].color.to_hex()?, "1d2021");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_skips_unsupported_replies() -> Result<(), Error> {
+        let recorded =
+            b"\x1b]10;rgba:ffff/ffff/ffff/ffff\x07\x1b]4;2;rgb:00/cd/00;3;rgba:0/0/0/f\x07\
+                         \x1b]12;rgb:ff/ff/ff\x07";
+        let replies = parse_osc_replies(recorded)?;
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].target, OscTarget::Palette(2));
+        assert_eq!(replies[0].color.to_hex()?, "00cd00");
+        assert_eq!(replies[1].target, OscTarget::Cursor);
+        assert_eq!(replies[1].color.to_hex()?, "ffffff");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_corrupt_reply() {
+        let recorded = b"\x1b]4;1;rgb:cdcd/0000/0000\x07\x1b]4;2;rgb:00cd/00\x07";
+        assert!(parse_osc_replies(recorded).is_err());
+        assert!(parse_osc_replies(b"\x1b]11;rgb:gg/00/00\x1b\\").is_err());
+    }
+
+    #[test]
+    fn test_parse_unterminated_reply() {
+        assert!(parse_osc_replies(b"\x1b]10;rgb:ff/ff/ff").is_err());
+    }
+}
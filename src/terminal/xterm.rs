@@ -0,0 +1,205 @@
+use std::sync::OnceLock;
+
+use crate::colors::{Color, CIELAB, SRGB};
+use crate::difference::DeltaE;
+use crate::encodings::{from_bytes, to_bytes};
+
+/// The SGR sequence that resets all graphic attributes, including colors.
+pub const SGR_RESET: &str = "\x1b[0m";
+
+/// The default system colors of xterm, in ANSI order (black, red, green, yellow, blue, magenta,
+/// cyan, white, followed by their bright variants).
+const SYSTEM_COLORS: [[u8; 3]; 16] = [
+    [0x00, 0x00, 0x00],
+    [0xcd, 0x00, 0x00],
+    [0x00, 0xcd, 0x00],
+    [0xcd, 0xcd, 0x00],
+    [0x00, 0x00, 0xee],
+    [0xcd, 0x00, 0xcd],
+    [0x00, 0xcd, 0xcd],
+    [0xe5, 0xe5, 0xe5],
+    [0x7f, 0x7f, 0x7f],
+    [0xff, 0x00, 0x00],
+    [0x00, 0xff, 0x00],
+    [0xff, 0xff, 0x00],
+    [0x5c, 0x5c, 0xff],
+    [0xff, 0x00, 0xff],
+    [0x00, 0xff, 0xff],
+    [0xff, 0xff, 0xff],
+];
+
+/// The component values used along each axis of the 6×6×6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+/// The first palette index that is not a system color.
+const CUBE_START: usize = 16;
+
+/// The number of colors in the terminal color palette that should be emitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 24-bit color, emitted directly without quantization.
+    TrueColor,
+    /// The xterm 256-color palette. Only the color cube and grayscale ramp (indices 16 through
+    /// 255) are considered, since the system colors are commonly redefined by color schemes.
+    Xterm256,
+    /// The 16 ANSI system colors.
+    Ansi16,
+}
+
+/// Whether a color applies to the text itself or to the cell behind it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Foreground,
+    Background,
+}
+
+/// The 16 system colors as defined by xterm's default resources.
+pub fn ansi_16_palette() -> [SRGB; 16] {
+    let mut palette = [SRGB::from_vector3(from_bytes([0, 0, 0])); 16];
+    for (color, bytes) in palette.iter_mut().zip(SYSTEM_COLORS.iter()) {
+        *color = SRGB::from_vector3(from_bytes(*bytes));
+    }
+    palette
+}
+
+/// The full xterm 256-color palette.
+///
+/// Indices 0 through 15 are the system colors, 16 through 231 are a 6×6×6 color cube indexed as
+/// 16 + 36 * r + 6 * g + b, and 232 through 255 are a 24-step grayscale ramp.
+///
+/// See https://en.wikipedia.org/wiki/ANSI_escape_code#8-bit for more information.
+pub fn xterm_256_palette() -> &'static [SRGB; 256] {
+    static PALETTE: OnceLock<[SRGB; 256]> = OnceLock::new();
+    PALETTE.get_or_init(build_xterm_256_palette)
+}
+
+fn build_xterm_256_palette() -> [SRGB; 256] {
+    let mut palette = [SRGB::from_vector3(from_bytes([0, 0, 0])); 256];
+    palette[..CUBE_START].copy_from_slice(&ansi_16_palette());
+    for r in 0..6 {
+        for g in 0..6 {
+            for b in 0..6 {
+                let bytes = [CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]];
+                palette[CUBE_START + 36 * r + 6 * g + b] = SRGB::from_vector3(from_bytes(bytes));
+            }
+        }
+    }
+    for i in 0..24 {
+        let level = 8 + 10 * i as u8;
+        palette[232 + i] = SRGB::from_vector3(from_bytes([level, level, level]));
+    }
+    palette
+}
+
+/// Find the index of the palette entry that is perceptually closest to a color.
+///
+/// Returns None if the palette is empty.
+pub fn nearest_index(color: &SRGB, palette: &[SRGB], metric: DeltaE) -> Option<usize> {
+    let target: CIELAB = color.to_color();
+    palette
+        .iter()
+        .map(|entry| metric.compute(&target, &entry.to_color()))
+        .enumerate()
+        .fold(
+            None,
+            |best: Option<(usize, f64)>, (i, distance)| match best {
+                Some((_, best_distance)) if best_distance <= distance => best,
+                _ => Some((i, distance)),
+            },
+        )
+        .map(|(i, _)| i)
+}
+
+/// Build the SGR (Select Graphic Rendition) escape sequence that sets a color in a terminal.
+///
+/// In the 256- and 16-color modes, the color is first quantized to the nearest entry of the xterm
+/// palette using the given metric.
+pub fn sgr_sequence(color: &SRGB, layer: Layer, mode: ColorMode, metric: DeltaE) -> String {
+    let parameters = match mode {
+        ColorMode::TrueColor => {
+            let [r, g, b] = to_bytes(color.to_vector3());
+            let prefix = match layer {
+                Layer::Foreground => 38,
+                Layer::Background => 48,
+            };
+            format!("{};2;{};{};{}", prefix, r, g, b)
+        }
+        ColorMode::Xterm256 => {
+            let palette = xterm_256_palette();
+            let index = CUBE_START
+                + nearest_index(color, &palette[CUBE_START..], metric)
+                    .expect("Palette is not empty.");
+            let prefix = match layer {
+                Layer::Foreground => 38,
+                Layer::Background => 48,
+            };
+            format!("{};5;{}", prefix, index)
+        }
+        ColorMode::Ansi16 => {
+            let index =
+                nearest_index(color, &ansi_16_palette(), metric).expect("Palette is not empty.");
+            let base = match (layer, index < 8) {
+                (Layer::Foreground, true) => 30,
+                (Layer::Background, true) => 40,
+                (Layer::Foreground, false) => 90 - 8,
+                (Layer::Background, false) => 100 - 8,
+            };
+            format!("{}", base + index)
+        }
+    };
+    format!("\x1b[{}m", parameters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::Error;
+
+    #[test]
+    fn test_xterm_256_palette_landmarks() -> Result<(), Error> {
+        let palette = xterm_256_palette();
+        assert_eq!(palette[1].to_hex()?, "cd0000");
+        assert_eq!(palette[16].to_hex()?, "000000");
+        assert_eq!(palette[196].to_hex()?, "ff0000");
+        assert_eq!(palette[231].to_hex()?, "ffffff");
+        assert_eq!(palette[232].to_hex()?, "080808");
+        assert_eq!(palette[255].to_hex()?, "eeeeee");
+        Ok(())
+    }
+
+    #[test]
+    fn test_nearest_index_exact_match() {
+        let palette = xterm_256_palette();
+        for metric in &[DeltaE::CIE76, DeltaE::CIE94, DeltaE::CIEDE2000] {
+            assert_eq!(
+                nearest_index(&palette[67], &palette[16..], *metric),
+                Some(67 - 16)
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_index_empty() {
+        let color = SRGB::from_vector3(from_bytes([1, 2, 3]));
+        assert_eq!(nearest_index(&color, &[], DeltaE::CIE76), None);
+    }
+
+    #[test]
+    fn test_sgr_sequences() -> Result<(), Error> {
+        let color = SRGB::from_hex("fe0102")?;
+        let metric = DeltaE::CIEDE2000;
+        assert_eq!(
+            sgr_sequence(&color, Layer::Foreground, ColorMode::TrueColor, metric),
+            "\x1b[38;2;254;1;2m"
+        );
+        assert_eq!(
+            sgr_sequence(&color, Layer::Background, ColorMode::Xterm256, metric),
+            "\x1b[48;5;196m"
+        );
+        assert_eq!(
+            sgr_sequence(&color, Layer::Foreground, ColorMode::Ansi16, metric),
+            "\x1b[91m"
+        );
+        Ok(())
+    }
+}
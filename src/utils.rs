@@ -1,4 +1,4 @@
-use super::colors::Color;
+use super::colors::{Color, SRGB};
 
 fn contrast_ratio_luminance(lighter: f64, darker: f64) -> f64 {
     (lighter + 0.05) / (darker + 0.05)
@@ -23,6 +23,59 @@ where
     contrast_ratio_luminance(lighter, darker)
 }
 
+/// Estimate screen luminance from sRGB components as specified by APCA.
+fn apca_luminance(color: SRGB) -> f64 {
+    let v = color.to_vector3().map(|x| x.clamp(0.0, 1.0).powf(2.4));
+    0.2126729 * v[0] + 0.7151522 * v[1] + 0.0721750 * v[2]
+}
+
+/// Soft clamp luminance near black to account for flare.
+fn apca_soft_clamp(luminance: f64) -> f64 {
+    let black_threshold = 0.022;
+    if luminance < black_threshold {
+        luminance + (black_threshold - luminance).powf(1.414)
+    } else {
+        luminance
+    }
+}
+
+/// Compute the APCA lightness contrast (Lc) of text against a background.
+///
+/// Unlike the contrast ratio, APCA is not symmetric. Positive values indicate dark text on a light
+/// background and negative values indicate light text on a dark background. Values range from
+/// roughly -108 to 106, and values near 0 indicate no readable contrast.
+///
+/// This implements the 0.0.98G-4g constants of the Accessible Perceptual Contrast Algorithm. See
+/// https://github.com/Myndex/SAPC-APCA for more information.
+pub fn apca_contrast<C>(text: C, background: C) -> f64
+where
+    C: Color,
+{
+    let text_luminance = apca_soft_clamp(apca_luminance(text.to_color()));
+    let background_luminance = apca_soft_clamp(apca_luminance(background.to_color()));
+
+    if (background_luminance - text_luminance).abs() < 0.0005 {
+        return 0.0;
+    }
+
+    let contrast = if background_luminance > text_luminance {
+        let sapc = (background_luminance.powf(0.56) - text_luminance.powf(0.57)) * 1.14;
+        if sapc < 0.1 {
+            0.0
+        } else {
+            sapc - 0.027
+        }
+    } else {
+        let sapc = (background_luminance.powf(0.65) - text_luminance.powf(0.62)) * 1.14;
+        if sapc > -0.1 {
+            0.0
+        } else {
+            sapc + 0.027
+        }
+    };
+    contrast * 100.0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,4 +104,18 @@ mod tests {
         let black = CIEXYZ::new(0.0, 0.0, 0.0);
         assert_eq!(contrast_ratio(black, white), 21.0);
     }
+
+    #[test]
+    fn test_apca_contrast_extremes() {
+        let white = CIEXYZ::new(0.95047, 1.0, 1.08883);
+        let black = CIEXYZ::new(0.0, 0.0, 0.0);
+        assert!((apca_contrast(black, white) - 106.04).abs() < 0.01);
+        assert!((apca_contrast(white, black) + 107.88).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_apca_contrast_equal() {
+        let gray = CIEXYZ::new(0.2, 0.2, 0.2);
+        assert_eq!(apca_contrast(gray, gray), 0.0);
+    }
 }
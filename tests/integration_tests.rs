@@ -2,7 +2,11 @@ extern crate color_schemes;
 
 use color_schemes::colors::{get_d65_ciexyz, Color, DisplayP3, CIELUV, CIEXYY, CIEXYZ, SRGB};
 use color_schemes::errors::Error;
-use color_schemes::utils::contrast_ratio;
+use color_schemes::schemes::ColorScheme;
+use color_schemes::terminal::{
+    ansi_16_palette, apply_scheme_sequence, parse_osc_replies, OscTarget,
+};
+use color_schemes::utils::{apca_contrast, contrast_ratio};
 use nalgebra::Vector3;
 
 macro_rules! assert_within_delta {
@@ -92,3 +96,31 @@ fn test_cieluv() {
         0.0001
     );
 }
+
+#[test]
+fn test_osc_scheme_round_trip() -> Result<(), Error> {
+    let scheme = ColorScheme {
+        foreground: SRGB::from_hex("ebdbb2")?,
+        background: SRGB::from_hex("282828")?,
+        cursor: SRGB::from_hex("fe8019")?,
+        ansi: ansi_16_palette(),
+    };
+    let replies = parse_osc_replies(apply_scheme_sequence(&scheme).as_bytes())?;
+    assert_eq!(replies.len(), 19);
+    for (index, reply) in replies.iter().take(16).enumerate() {
+        assert_eq!(reply.target, OscTarget::Palette(index as u8));
+        assert_eq!(reply.color.to_hex()?, scheme.ansi[index].to_hex()?);
+    }
+    assert_eq!(replies[18].target, OscTarget::Cursor);
+    assert_eq!(replies[18].color.to_hex()?, "fe8019");
+    Ok(())
+}
+
+#[test]
+fn test_apca_contrast_reference_values() -> Result<(), Error> {
+    let gray = SRGB::from_hex("888")?;
+    let white = SRGB::from_hex("fff")?;
+    assert_within_delta!(apca_contrast(gray, white), 63.056, 0.001);
+    assert_within_delta!(apca_contrast(white, gray), -68.541, 0.001);
+    Ok(())
+}